@@ -0,0 +1,15 @@
+use mpv_socket::{Error, MpvSocket};
+
+fn main() -> Result<(), Error> {
+    pretty_env_logger::init_timed();
+
+    let mut mpv_socket = MpvSocket::connect(r#"\\.\pipe\mpv-socket"#)?;
+
+    // Print all events until the player quits:
+    for result in mpv_socket.events() {
+        let event = result?;
+        log::info!("Event: {:?}", event);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,372 @@
+//! Async mpv socket connection for tokio.
+//!
+//! Requires the `tokio` feature and a Unix platform.
+
+use std::marker::PhantomData;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use futures_core::Stream;
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::sync::{mpsc, oneshot};
+use tokio::task::JoinHandle;
+
+use crate::event::Event;
+use crate::protocol::{Command, Request};
+use crate::router::{property_change_data, EventSender, ReplySender};
+use crate::{Error, NamedCommand, PropertyName, Result, TryFromValue, Value};
+
+/// Async mpv socket connection.
+///
+/// The async counterpart of [`MpvClient`]:
+/// the client can be cloned and shared between tasks,
+/// and a background task reads from the socket
+/// and passes replies and events to the tasks waiting for them.
+///
+/// The connection is closed when the last clone of the client is dropped.
+///
+/// [`MpvClient`]: ../struct.MpvClient.html
+#[derive(Clone)]
+pub struct MpvClient {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    requests: mpsc::UnboundedSender<Vec<u8>>,
+    request_timeout: Option<Duration>,
+    router: Arc<Router>,
+    reader_task: JoinHandle<()>,
+}
+
+impl Drop for Inner {
+    fn drop(&mut self) {
+        // The writer task ends after the queued requests once `requests` is dropped.
+        self.reader_task.abort();
+    }
+}
+
+type Router = crate::router::Router<oneshot::Sender<Result<Value>>, mpsc::UnboundedSender<Event>>;
+
+impl ReplySender for oneshot::Sender<Result<Value>> {
+    fn send_reply(self, result: Result<Value>) {
+        let _ = self.send(result);
+    }
+}
+
+impl EventSender for mpsc::UnboundedSender<Event> {
+    fn send_event(&self, event: Event) -> bool {
+        self.send(event).is_ok()
+    }
+}
+
+impl MpvClient {
+    /// Must be called from within a tokio runtime.
+    pub(crate) fn new<R, W>(reader: R, writer: W, request_timeout: Option<Duration>) -> MpvClient
+    where
+        R: AsyncRead + Send + Unpin + 'static,
+        W: AsyncWrite + Send + Unpin + 'static,
+    {
+        let router = Arc::new(Router::new());
+
+        let reader_router = Arc::clone(&router);
+        let reader_task = tokio::spawn(async move { read_loop(&reader_router, reader).await });
+
+        // Requests are written by a single task,
+        // so a cancelled request never leaves a partial line on the socket.
+        let (requests, request_receiver) = mpsc::unbounded_channel();
+        let writer_router = Arc::clone(&router);
+        tokio::spawn(async move { write_loop(&writer_router, writer, request_receiver).await });
+
+        MpvClient {
+            inner: Arc::new(Inner {
+                requests,
+                request_timeout,
+                router,
+                reader_task,
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+impl MpvClient {
+    pub(crate) fn num_pending_replies(&self) -> usize {
+        self.inner.router.num_pending_replies()
+    }
+}
+
+impl MpvClient {
+    /// Connects to an mpv socket with the default builder settings.
+    ///
+    /// See [`MpvSocket::connect`] for how to create the socket
+    /// and [`MpvSocketBuilder::connect_async_client`] for the timeouts.
+    ///
+    /// [`MpvSocket::connect`]: ../struct.MpvSocket.html#method.connect
+    /// [`MpvSocketBuilder::connect_async_client`]: ../struct.MpvSocketBuilder.html#method.connect_async_client
+    pub async fn connect<P: AsRef<std::path::Path>>(path: P) -> Result<MpvClient> {
+        crate::MpvSocket::builder().connect_async_client(path).await
+    }
+}
+
+impl MpvClient {
+    /// Return the name of the client as string.
+    ///
+    /// This is the string "ipc-N" with N being an integer number.
+    pub async fn client_name(&self) -> Result<String> {
+        self.send_recv_convert_command(Command::ClientName).await
+    }
+
+    /// Return the value of the given property.
+    ///
+    /// See [`MpvSocket::get_property`] for more information.
+    ///
+    /// [`MpvSocket::get_property`]: ../struct.MpvSocket.html#method.get_property
+    pub async fn get_property<T>(&self, property: impl Into<PropertyName>) -> Result<T>
+    where
+        T: TryFromValue,
+    {
+        self.send_recv_convert_command(Command::GetProperty(property.into()))
+            .await
+    }
+
+    /// Set the given property to the given value.
+    ///
+    /// See [`Properties`] for more information about properties.
+    ///
+    /// [`Properties`]: https://mpv.io/manual/master/#properties
+    pub async fn set_property(
+        &self,
+        property: impl Into<PropertyName>,
+        value: impl Into<Value>,
+    ) -> Result<()> {
+        self.send_recv_command(Command::SetProperty(property.into(), value.into()))
+            .await?;
+        Ok(())
+    }
+
+    /// Run the given input command with the given arguments and return its result.
+    ///
+    /// See [`MpvSocket::command`] for more information.
+    ///
+    /// [`MpvSocket::command`]: ../struct.MpvSocket.html#method.command
+    pub async fn command<T>(&self, name: &str, args: impl IntoIterator<Item = Value>) -> Result<T>
+    where
+        T: TryFromValue,
+    {
+        let args = args.into_iter().collect();
+        self.send_recv_convert_command(Command::Raw(name.to_owned(), args))
+            .await
+    }
+
+    /// Run the given input command with named arguments and return its result.
+    ///
+    /// See [`NamedCommand`] for more information.
+    ///
+    /// [`NamedCommand`]: ../struct.NamedCommand.html
+    pub async fn command_named<T>(&self, command: NamedCommand) -> Result<T>
+    where
+        T: TryFromValue,
+    {
+        self.send_recv_convert_command(Command::Named(command))
+            .await
+    }
+
+    /// Watch a property for changes.
+    ///
+    /// The returned stream yields the value of the property whenever it changes,
+    /// and ends when the connection is closed.
+    ///
+    /// The property is unobserved when the stream is dropped.
+    pub async fn observe_property<T>(
+        &self,
+        property: impl Into<PropertyName>,
+    ) -> Result<PropertyStream<T>>
+    where
+        T: TryFromValue,
+    {
+        let observe_id = self.inner.router.next_observe_id();
+        let (sender, receiver) = mpsc::unbounded_channel();
+        self.inner.router.subscribe(Some(observe_id), sender)?;
+
+        if let Err(error) = self
+            .send_recv_command(Command::ObserveProperty(observe_id, property.into()))
+            .await
+        {
+            self.inner.router.unsubscribe(observe_id);
+            return Err(error);
+        }
+
+        Ok(PropertyStream {
+            client: self.clone(),
+            observe_id,
+            receiver,
+            _marker: PhantomData,
+        })
+    }
+
+    /// Stream all events sent by mpv.
+    ///
+    /// The returned stream ends when the connection is closed.
+    pub fn events(&self) -> Result<EventStream> {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        self.inner.router.subscribe(None, sender)?;
+        Ok(EventStream { receiver })
+    }
+
+    async fn send_recv_convert_command<T>(&self, command: Command) -> Result<T>
+    where
+        T: TryFromValue,
+    {
+        T::try_from(self.send_recv_command(command).await?)
+    }
+
+    async fn send_recv_command(&self, command: Command) -> Result<Value> {
+        let request = Request {
+            command,
+            request_id: self.inner.router.next_request_id(),
+        };
+        let mut req_json = serde_json::to_vec(&request)?;
+        if log::log_enabled!(log::Level::Trace) {
+            log::trace!("sending: {}", String::from_utf8_lossy(&req_json));
+        }
+        req_json.push(b'\n');
+
+        // Dropping this future forgets the request, even while waiting for the reply.
+        let (sender, receiver) = oneshot::channel();
+        let _pending_reply = self
+            .inner
+            .router
+            .add_pending_reply(request.request_id, sender)?;
+
+        if self.inner.requests.send(req_json).is_err() {
+            return Err(Error::Closed);
+        }
+
+        let reply = match self.inner.request_timeout {
+            Some(timeout) => match tokio::time::timeout(timeout, receiver).await {
+                Ok(reply) => reply,
+                Err(_elapsed) => return Err(Error::Timeout),
+            },
+            None => receiver.await,
+        };
+        match reply {
+            Ok(result) => result,
+            Err(_) => Err(Error::Closed),
+        }
+    }
+}
+
+async fn read_loop(router: &Router, reader: impl AsyncRead + Unpin) {
+    let mut reader = BufReader::new(reader);
+    let mut line = Vec::with_capacity(128);
+
+    loop {
+        match reader.read_until(b'\n', &mut line).await {
+            Ok(0) => break,
+            Ok(_) => {
+                router.dispatch(&line);
+                line.clear();
+            }
+            Err(io_error) => {
+                log::error!("error while reading from mpv socket: {}", io_error);
+                break;
+            }
+        }
+    }
+
+    router.close();
+}
+
+async fn write_loop(
+    router: &Router,
+    mut writer: impl AsyncWrite + Unpin,
+    mut requests: mpsc::UnboundedReceiver<Vec<u8>>,
+) {
+    while let Some(req_json) = requests.recv().await {
+        let write_result = match writer.write_all(&req_json).await {
+            Ok(()) => writer.flush().await,
+            Err(error) => Err(error),
+        };
+        if let Err(io_error) = write_result {
+            log::error!("error while writing to mpv socket: {}", io_error);
+            router.close();
+            return;
+        }
+    }
+}
+
+/// Stream of the changes of an observed property.
+///
+/// Returned by [`MpvClient::observe_property`].
+///
+/// [`MpvClient::observe_property`]: ./struct.MpvClient.html#method.observe_property
+pub struct PropertyStream<T> {
+    client: MpvClient,
+    observe_id: i64,
+    receiver: mpsc::UnboundedReceiver<Event>,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T: TryFromValue> Stream for PropertyStream<T> {
+    type Item = Result<T>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Result<T>>> {
+        loop {
+            match self.receiver.poll_recv(cx) {
+                Poll::Ready(Some(event)) => {
+                    if let Some(data) = property_change_data(event) {
+                        return Poll::Ready(Some(T::try_from(data)));
+                    }
+                }
+                Poll::Ready(None) => return Poll::Ready(None),
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
+
+impl<T> Drop for PropertyStream<T> {
+    fn drop(&mut self) {
+        self.client.inner.router.unsubscribe(self.observe_id);
+        if self.client.inner.router.is_closed() {
+            return;
+        }
+
+        // Unobserving needs a round trip to mpv,
+        // which can only be done in the background while inside of a runtime.
+        let runtime = match tokio::runtime::Handle::try_current() {
+            Ok(runtime) => runtime,
+            Err(_) => return,
+        };
+        let client = self.client.clone();
+        let observe_id = self.observe_id;
+        runtime.spawn(async move {
+            if let Err(error) = client
+                .send_recv_command(Command::UnobserveProperty(observe_id))
+                .await
+            {
+                log::error!("error while dropping property stream: {}", error);
+            }
+        });
+    }
+}
+
+/// Stream of all events of an [`MpvClient`].
+///
+/// Returned by [`MpvClient::events`].
+///
+/// [`MpvClient`]: ./struct.MpvClient.html
+/// [`MpvClient::events`]: ./struct.MpvClient.html#method.events
+pub struct EventStream {
+    receiver: mpsc::UnboundedReceiver<Event>,
+}
+
+impl Stream for EventStream {
+    type Item = Event;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Event>> {
+        self.receiver.poll_recv(cx)
+    }
+}
@@ -0,0 +1,118 @@
+use crate::protocol::Command;
+use crate::{Error, MpvSocket, NamedCommand, PropertyName, Result, TryFromValue, Value};
+
+/// Commands which are sent to mpv at once.
+///
+/// All requests of a batch are written in one go
+/// and the replies are collected afterwards,
+/// so reading many properties only costs a single round trip.
+///
+/// Created with [`MpvSocket::batch`].
+///
+/// # Example
+///
+/// ```no_run
+/// use mpv_socket::{MpvSocket, Property};
+///
+/// # fn main() -> mpv_socket::Result<()> {
+/// let mut mpv = MpvSocket::connect("/tmp/mpv-socket")?;
+/// let mut results = mpv
+///     .batch()
+///     .get(Property::Duration)
+///     .get(Property::TimePos)
+///     .set(Property::Pause, true)
+///     .send()?;
+/// let duration: f64 = results.take(0)?;
+/// let time_pos: f64 = results.take(1)?;
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [`MpvSocket::batch`]: ./struct.MpvSocket.html#method.batch
+pub struct Batch<'a> {
+    mpv: &'a mut MpvSocket,
+    commands: Vec<Command>,
+}
+
+impl<'a> Batch<'a> {
+    pub(crate) fn new(mpv: &'a mut MpvSocket) -> Batch<'a> {
+        Batch {
+            mpv,
+            commands: Vec::new(),
+        }
+    }
+
+    /// Add reading the value of the given property.
+    pub fn get(mut self, property: impl Into<PropertyName>) -> Batch<'a> {
+        self.commands.push(Command::GetProperty(property.into()));
+        self
+    }
+
+    /// Add setting the given property to the given value.
+    pub fn set(mut self, property: impl Into<PropertyName>, value: impl Into<Value>) -> Batch<'a> {
+        self.commands
+            .push(Command::SetProperty(property.into(), value.into()));
+        self
+    }
+
+    /// Add running the given input command with the given arguments.
+    pub fn command(mut self, name: &str, args: impl IntoIterator<Item = Value>) -> Batch<'a> {
+        let args = args.into_iter().collect();
+        self.commands.push(Command::Raw(name.to_owned(), args));
+        self
+    }
+
+    /// Add running the given input command with named arguments.
+    pub fn command_named(mut self, command: NamedCommand) -> Batch<'a> {
+        self.commands.push(Command::Named(command));
+        self
+    }
+
+    /// Send all commands and wait for all of their replies.
+    ///
+    /// An error is only returned if the batch could not be sent or its replies could not be read,
+    /// errors of single commands are returned by [`BatchResults::take`].
+    ///
+    /// [`BatchResults::take`]: ./struct.BatchResults.html#method.take
+    pub fn send(self) -> Result<BatchResults> {
+        let results = self.mpv.send_recv_batch(self.commands)?;
+        Ok(BatchResults {
+            results: results.into_iter().map(Some).collect(),
+        })
+    }
+}
+
+/// Results of a [`Batch`], in the order the commands were added.
+///
+/// [`Batch`]: ./struct.Batch.html
+#[derive(Debug)]
+pub struct BatchResults {
+    results: Vec<Option<Result<Value>>>,
+}
+
+impl BatchResults {
+    /// Number of results, which is the number of commands in the batch.
+    pub fn len(&self) -> usize {
+        self.results.len()
+    }
+
+    /// Whether the batch contained no commands.
+    pub fn is_empty(&self) -> bool {
+        self.results.is_empty()
+    }
+
+    /// Take the result of the command at the given index.
+    ///
+    /// Returns an error if the command failed,
+    /// the value could not be converted,
+    /// or the result was already taken.
+    pub fn take<T>(&mut self, index: usize) -> Result<T>
+    where
+        T: TryFromValue,
+    {
+        match self.results.get_mut(index).and_then(Option::take) {
+            Some(result) => T::try_from(result?),
+            None => Err(Error::other(format!("no batch result at index {}", index))),
+        }
+    }
+}
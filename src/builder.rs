@@ -0,0 +1,310 @@
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+#[cfg(unix)]
+use crate::MpvClient;
+#[cfg(all(unix, feature = "process"))]
+use crate::MpvProcess;
+use crate::{Error, MpvSocket, ReadWrite, Result};
+
+/// Builder for an [`MpvSocket`] connection with custom timeouts and retries.
+///
+/// Created with [`MpvSocket::builder`].
+///
+/// # Example
+///
+/// ```no_run
+/// use std::time::Duration;
+/// use mpv_socket::MpvSocket;
+///
+/// # fn main() -> mpv_socket::Result<()> {
+/// let mpv = MpvSocket::builder()
+///     .connect_timeout(Some(Duration::from_secs(5)))
+///     .request_timeout(Some(Duration::from_secs(2)))
+///     .connect("/tmp/mpv-socket")?;
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [`MpvSocket`]: ./struct.MpvSocket.html
+/// [`MpvSocket::builder`]: ./struct.MpvSocket.html#method.builder
+#[derive(Debug, Clone)]
+pub struct MpvSocketBuilder {
+    read_timeout: Option<Duration>,
+    pub(crate) write_timeout: Option<Duration>,
+    connect_timeout: Option<Duration>,
+    retry_interval: Duration,
+    request_timeout: Option<Duration>,
+    event_timeout: Option<Duration>,
+}
+
+impl MpvSocketBuilder {
+    pub(crate) fn new() -> MpvSocketBuilder {
+        MpvSocketBuilder {
+            read_timeout: Some(Duration::from_secs(10)),
+            write_timeout: Some(Duration::from_secs(10)),
+            connect_timeout: None,
+            retry_interval: Duration::from_millis(100),
+            request_timeout: None,
+            event_timeout: None,
+        }
+    }
+
+    /// Timeout of a single read from the socket, 10 seconds by default.
+    ///
+    /// If no reply arrives within this time, the request fails with [`Error::Timeout`].
+    /// `None` blocks until data arrives.
+    /// Event iterators are not limited by this timeout, see [`event_timeout`].
+    /// Timeouts are not supported for named pipes and are ignored on Windows.
+    ///
+    /// [`Error::Timeout`]: ./enum.Error.html#variant.Timeout
+    /// [`event_timeout`]: #method.event_timeout
+    pub fn read_timeout(mut self, timeout: Option<Duration>) -> MpvSocketBuilder {
+        self.read_timeout = timeout;
+        self
+    }
+
+    /// Timeout of a single write to the socket, 10 seconds by default.
+    ///
+    /// `None` blocks until the data is written.
+    /// Timeouts are not supported for named pipes and are ignored on Windows.
+    pub fn write_timeout(mut self, timeout: Option<Duration>) -> MpvSocketBuilder {
+        self.write_timeout = timeout;
+        self
+    }
+
+    /// Keep trying to connect for the given duration.
+    ///
+    /// This is useful if mpv was just started and has not created the socket yet.
+    /// Attempts are repeated while the socket does not exist or refuses connections,
+    /// other errors are returned immediately.
+    /// If no connection could be made in time, [`Error::Timeout`] is returned.
+    ///
+    /// By default, or with `None`, only a single attempt is made.
+    ///
+    /// [`Error::Timeout`]: ./enum.Error.html#variant.Timeout
+    pub fn connect_timeout(mut self, timeout: Option<Duration>) -> MpvSocketBuilder {
+        self.connect_timeout = timeout;
+        self
+    }
+
+    /// Time to wait between connection attempts, 100 milliseconds by default.
+    ///
+    /// Only used together with [`connect_timeout`].
+    ///
+    /// [`connect_timeout`]: #method.connect_timeout
+    pub fn retry_interval(mut self, interval: Duration) -> MpvSocketBuilder {
+        self.retry_interval = interval;
+        self
+    }
+
+    /// Deadline for each request, from sending it until its reply arrives.
+    ///
+    /// Unlike the read timeout, this also applies if mpv keeps sending events
+    /// while the reply is missing.
+    /// A request which misses its deadline fails with [`Error::Timeout`].
+    /// `None`, the default, only applies the read timeout.
+    ///
+    /// [`Error::Timeout`]: ./enum.Error.html#variant.Timeout
+    pub fn request_timeout(mut self, timeout: Option<Duration>) -> MpvSocketBuilder {
+        self.request_timeout = timeout;
+        self
+    }
+
+    /// Maximum time to wait for the next event while iterating events.
+    ///
+    /// By default, or with `None`, event iterators wait forever,
+    /// as an idle or paused player may not send events for a long time.
+    /// With this timeout they return [`Error::Timeout`] instead.
+    ///
+    /// [`Error::Timeout`]: ./enum.Error.html#variant.Timeout
+    pub fn event_timeout(mut self, timeout: Option<Duration>) -> MpvSocketBuilder {
+        self.event_timeout = timeout;
+        self
+    }
+
+    /// Connects to an mpv socket.
+    ///
+    /// See [`MpvSocket::connect`] for the expected path.
+    ///
+    /// [`MpvSocket::connect`]: ./struct.MpvSocket.html#method.connect
+    pub fn connect<P: AsRef<Path>>(&self, path: P) -> Result<MpvSocket> {
+        let path = path.as_ref();
+        log::info!("connecting to: {}", path.display());
+
+        let socket = self.retry(|| crate::transport::open(path, self))?;
+        Ok(self.socket(socket))
+    }
+
+    /// Connects to an mpv socket with a thread-safe [`MpvClient`].
+    ///
+    /// The connect timeout and retry interval are used like for [`connect`].
+    /// The request timeout, or the read timeout if no request timeout is set,
+    /// limits how long each request waits for its reply.
+    /// The event timeout is not used.
+    ///
+    /// Requires a Unix platform.
+    ///
+    /// [`MpvClient`]: ./struct.MpvClient.html
+    /// [`connect`]: #method.connect
+    #[cfg(unix)]
+    pub fn connect_client<P: AsRef<Path>>(&self, path: P) -> Result<MpvClient> {
+        let path = path.as_ref();
+        log::info!("connecting to: {}", path.display());
+
+        // The reader thread blocks until data arrives, deadlines are handled per request.
+        let socket =
+            self.retry(|| crate::transport::connect_unix(path, None, self.write_timeout))?;
+        MpvClient::from_unix_stream(socket, self.request_timeout.or(self.read_timeout))
+    }
+
+    /// Connects to an mpv socket with an [`async_client::MpvClient`].
+    ///
+    /// The timeouts and retries are used like for [`connect_client`],
+    /// the runtime needs to have the time driver enabled.
+    ///
+    /// Requires the `tokio` feature and a Unix platform.
+    ///
+    /// [`async_client::MpvClient`]: ./async_client/struct.MpvClient.html
+    /// [`connect_client`]: #method.connect_client
+    #[cfg(all(unix, feature = "tokio"))]
+    pub async fn connect_async_client<P: AsRef<Path>>(
+        &self,
+        path: P,
+    ) -> Result<crate::async_client::MpvClient> {
+        let path = path.as_ref();
+        log::info!("connecting to: {}", path.display());
+
+        let deadline = self.connect_deadline();
+        let socket = loop {
+            match tokio::net::UnixStream::connect(path).await {
+                Ok(socket) => break socket,
+                Err(error) => tokio::time::sleep(self.retry_delay(error, deadline)?).await,
+            }
+        };
+
+        let (reader, writer) = socket.into_split();
+        Ok(crate::async_client::MpvClient::new(
+            reader,
+            writer,
+            self.request_timeout.or(self.read_timeout),
+        ))
+    }
+
+    /// Repeats `open` while it fails with a retryable error until the connect timeout.
+    fn retry<T>(&self, mut open: impl FnMut() -> std::io::Result<T>) -> Result<T> {
+        let deadline = self.connect_deadline();
+        loop {
+            match open() {
+                Ok(socket) => return Ok(socket),
+                Err(error) => std::thread::sleep(self.retry_delay(error, deadline)?),
+            }
+        }
+    }
+
+    fn connect_deadline(&self) -> Option<Instant> {
+        self.connect_timeout.map(|timeout| Instant::now() + timeout)
+    }
+
+    /// Time to wait before the next connection attempt after the given error,
+    /// or the error to return if no further attempt should be made.
+    fn retry_delay(&self, error: std::io::Error, deadline: Option<Instant>) -> Result<Duration> {
+        if !crate::transport::is_retryable(&error) {
+            return Err(error.into());
+        }
+        match deadline {
+            Some(deadline) if Instant::now() < deadline => {
+                log::debug!("retrying to connect after error: {}", error);
+                Ok(self.retry_interval)
+            }
+            Some(_) => Err(Error::Timeout),
+            None => Err(error.into()),
+        }
+    }
+
+    /// Spawns mpv with the given command and connects to it over an inherited socket pair.
+    ///
+    /// The `--input-ipc-client` option is appended to the arguments of the command,
+    /// so the command must not contain `--`, after which mpv treats all arguments as files.
+    /// The connect timeout and retry interval are not used,
+    /// as the connection is established before mpv is started.
+    ///
+    /// See [`MpvProcess`] for more information.
+    ///
+    /// Requires the `process` feature and a Unix platform.
+    ///
+    /// [`MpvProcess`]: ./struct.MpvProcess.html
+    #[cfg(all(unix, feature = "process"))]
+    pub fn spawn(&self, command: std::process::Command) -> Result<(MpvProcess, MpvSocket)> {
+        if command.get_args().any(|arg| arg == "--") {
+            return Err(Error::other(
+                "the command must not contain `--`, as `--input-ipc-client` is appended to it",
+            ));
+        }
+        self.spawn_with_args(command, std::iter::empty::<&std::ffi::OsStr>())
+    }
+
+    /// Spawns mpv with the given command,
+    /// adding the `--input-ipc-client` option before the given arguments.
+    #[cfg(all(unix, feature = "process"))]
+    pub(crate) fn spawn_with_args<I, S>(
+        &self,
+        command: std::process::Command,
+        args: I,
+    ) -> Result<(MpvProcess, MpvSocket)>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<std::ffi::OsStr>,
+    {
+        let (process, socket) = crate::process::spawn(command, args)?;
+        socket.set_read_timeout(self.effective_read_timeout())?;
+        socket.set_write_timeout(self.write_timeout)?;
+
+        Ok((process, self.socket(Box::new(socket))))
+    }
+
+    pub(crate) fn socket(&self, socket: Box<dyn ReadWrite>) -> MpvSocket {
+        let mut mpv = MpvSocket::new(socket);
+        mpv.read_timeout = self.read_timeout;
+        mpv.request_timeout = self.request_timeout;
+        mpv.event_timeout = self.event_timeout;
+        mpv
+    }
+
+    /// The socket read timeout, which also needs to wake up blocked reads for the deadlines.
+    #[cfg_attr(not(unix), allow(dead_code))]
+    pub(crate) fn effective_read_timeout(&self) -> Option<Duration> {
+        [self.read_timeout, self.request_timeout, self.event_timeout]
+            .iter()
+            .flatten()
+            .min()
+            .copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(unix)]
+    #[test]
+    fn connect_to_missing_socket() {
+        let error = MpvSocket::builder()
+            .connect("/tmp/mpv-socket-does-not-exist")
+            .err()
+            .unwrap();
+        assert!(matches!(error, Error::Io(error) if error.kind() == std::io::ErrorKind::NotFound));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn connect_timeout_to_missing_socket() {
+        let error = MpvSocket::builder()
+            .connect_timeout(Some(Duration::from_millis(50)))
+            .retry_interval(Duration::from_millis(10))
+            .connect("/tmp/mpv-socket-does-not-exist")
+            .err()
+            .unwrap();
+        assert!(matches!(error, Error::Timeout));
+    }
+}
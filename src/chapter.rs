@@ -0,0 +1,50 @@
+use serde::Deserialize;
+
+use crate::property::deserialize_value;
+use crate::{Result, TryFromValue, Value};
+
+/// Entry of the [`Property::ChapterList`].
+///
+/// [`Property::ChapterList`]: ./enum.Property.html#variant.ChapterList
+#[derive(Debug, Clone, Deserialize, PartialEq)]
+pub struct Chapter {
+    /// Chapter title as stored in the file.
+    ///
+    /// Not always available.
+    pub title: Option<String>,
+    /// Chapter start time in seconds.
+    pub time: f64,
+}
+
+impl TryFromValue for Chapter {
+    fn try_from(value: Value) -> Result<Chapter> {
+        deserialize_value(value)
+    }
+}
+
+/// Entry of the [`Property::EditionList`].
+///
+/// [`Property::EditionList`]: ./enum.Property.html#variant.EditionList
+#[derive(Debug, Clone, Deserialize, PartialEq)]
+pub struct Edition {
+    /// Edition ID as integer.
+    ///
+    /// Use this to set the [`Property::Edition`] property.
+    /// Currently, this is the same as the edition index.
+    ///
+    /// [`Property::Edition`]: ./enum.Property.html#variant.Edition
+    pub id: i64,
+    /// Edition title as stored in the file.
+    ///
+    /// Not always available.
+    pub title: Option<String>,
+    /// `true` if this is the default edition.
+    #[serde(default)]
+    pub default: bool,
+}
+
+impl TryFromValue for Edition {
+    fn try_from(value: Value) -> Result<Edition> {
+        deserialize_value(value)
+    }
+}
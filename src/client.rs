@@ -0,0 +1,339 @@
+use std::io::{BufRead, BufReader, Read, Write};
+use std::marker::PhantomData;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use crate::event::Event;
+use crate::protocol::{Command, Request};
+use crate::router::property_change_data;
+use crate::{Error, NamedCommand, PropertyName, Result, TryFromValue, Value};
+
+/// Thread-safe mpv socket connection.
+///
+/// Unlike [`MpvSocket`], all methods take `&self`
+/// and the client can be cloned and shared between threads.
+/// A background thread reads from the socket
+/// and passes replies and events to the threads waiting for them,
+/// so commands can be sent while properties are observed.
+///
+/// The connection is closed when the last clone of the client is dropped.
+///
+/// Use [`MpvSocketBuilder::connect_client`] to configure timeouts and retries.
+///
+/// Requires a Unix platform.
+///
+/// [`MpvSocket`]: ./struct.MpvSocket.html
+/// [`MpvSocketBuilder::connect_client`]: ./struct.MpvSocketBuilder.html#method.connect_client
+#[derive(Clone)]
+pub struct MpvClient {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    writer: Mutex<Box<dyn Write + Send>>,
+    request_timeout: Option<Duration>,
+    router: Arc<Router>,
+    shutdown: Box<dyn Fn() + Send + Sync>,
+}
+
+impl Drop for Inner {
+    fn drop(&mut self) {
+        (self.shutdown)();
+    }
+}
+
+type Router = crate::router::Router<Sender<Result<Value>>, Sender<Event>>;
+
+impl MpvClient {
+    pub(crate) fn new<R, W>(
+        reader: R,
+        writer: W,
+        request_timeout: Option<Duration>,
+        shutdown: Box<dyn Fn() + Send + Sync>,
+    ) -> MpvClient
+    where
+        R: Read + Send + 'static,
+        W: Write + Send + 'static,
+    {
+        let router = Arc::new(Router::new());
+
+        let reader_router = Arc::clone(&router);
+        std::thread::Builder::new()
+            .name(String::from("mpv-socket-reader"))
+            .spawn(move || read_loop(&reader_router, reader))
+            .expect("failed to spawn mpv socket reader thread");
+
+        MpvClient {
+            inner: Arc::new(Inner {
+                writer: Mutex::new(Box::new(writer)),
+                request_timeout,
+                router,
+                shutdown,
+            }),
+        }
+    }
+}
+
+impl MpvClient {
+    /// Connects to an mpv socket.
+    ///
+    /// See [`MpvSocket::connect`] for how to create the socket.
+    ///
+    /// Requests which get no reply within 10 seconds fail with [`Error::Timeout`],
+    /// use [`MpvSocketBuilder::connect_client`] to configure this.
+    ///
+    /// [`MpvSocket::connect`]: ./struct.MpvSocket.html#method.connect
+    /// [`Error::Timeout`]: ./enum.Error.html#variant.Timeout
+    /// [`MpvSocketBuilder::connect_client`]: ./struct.MpvSocketBuilder.html#method.connect_client
+    pub fn connect<P: AsRef<std::path::Path>>(path: P) -> Result<MpvClient> {
+        crate::MpvSocket::builder().connect_client(path)
+    }
+
+    pub(crate) fn from_unix_stream(
+        socket: std::os::unix::net::UnixStream,
+        request_timeout: Option<Duration>,
+    ) -> Result<MpvClient> {
+        use std::net::Shutdown;
+
+        let reader = socket.try_clone()?;
+        let shutdown_socket = socket.try_clone()?;
+        let shutdown = Box::new(move || {
+            let _ = shutdown_socket.shutdown(Shutdown::Both);
+        });
+        Ok(MpvClient::new(reader, socket, request_timeout, shutdown))
+    }
+}
+
+impl MpvClient {
+    /// Return the name of the client as string.
+    ///
+    /// This is the string "ipc-N" with N being an integer number.
+    pub fn client_name(&self) -> Result<String> {
+        self.send_recv_convert_command(Command::ClientName)
+    }
+
+    /// Return the value of the given property.
+    ///
+    /// See [`MpvSocket::get_property`] for more information.
+    ///
+    /// [`MpvSocket::get_property`]: ./struct.MpvSocket.html#method.get_property
+    pub fn get_property<T>(&self, property: impl Into<PropertyName>) -> Result<T>
+    where
+        T: TryFromValue,
+    {
+        self.send_recv_convert_command(Command::GetProperty(property.into()))
+    }
+
+    /// Set the given property to the given value.
+    ///
+    /// See [`Properties`] for more information about properties.
+    ///
+    /// [`Properties`]: https://mpv.io/manual/master/#properties
+    pub fn set_property(
+        &self,
+        property: impl Into<PropertyName>,
+        value: impl Into<Value>,
+    ) -> Result<()> {
+        self.send_recv_command(Command::SetProperty(property.into(), value.into()))?;
+        Ok(())
+    }
+
+    /// Run the given input command with the given arguments and return its result.
+    ///
+    /// See [`MpvSocket::command`] for more information.
+    ///
+    /// [`MpvSocket::command`]: ./struct.MpvSocket.html#method.command
+    pub fn command<T>(&self, name: &str, args: impl IntoIterator<Item = Value>) -> Result<T>
+    where
+        T: TryFromValue,
+    {
+        let args = args.into_iter().collect();
+        self.send_recv_convert_command(Command::Raw(name.to_owned(), args))
+    }
+
+    /// Run the given input command with named arguments and return its result.
+    ///
+    /// See [`NamedCommand`] for more information.
+    ///
+    /// [`NamedCommand`]: ./struct.NamedCommand.html
+    pub fn command_named<T>(&self, command: NamedCommand) -> Result<T>
+    where
+        T: TryFromValue,
+    {
+        self.send_recv_convert_command(Command::Named(command))
+    }
+
+    /// Watch a property for changes.
+    ///
+    /// The returned iterator yields the value of the property whenever it changes,
+    /// and can be moved to another thread.
+    /// Unlike [`MpvSocket::observe_property`], it does not end when the current file ends,
+    /// only when the connection is closed.
+    ///
+    /// The property is unobserved when the iterator is dropped.
+    ///
+    /// [`MpvSocket::observe_property`]: ./struct.MpvSocket.html#method.observe_property
+    pub fn observe_property<T>(
+        &self,
+        property: impl Into<PropertyName>,
+    ) -> Result<PropertyObserver<T>>
+    where
+        T: TryFromValue,
+    {
+        let observe_id = self.inner.router.next_observe_id();
+        let (sender, receiver) = mpsc::channel();
+        self.inner.router.subscribe(Some(observe_id), sender)?;
+
+        if let Err(error) =
+            self.send_recv_command(Command::ObserveProperty(observe_id, property.into()))
+        {
+            self.inner.router.unsubscribe(observe_id);
+            return Err(error);
+        }
+
+        Ok(PropertyObserver {
+            client: self.clone(),
+            observe_id,
+            receiver,
+            _marker: PhantomData,
+        })
+    }
+
+    /// Iterate over all events sent by mpv.
+    ///
+    /// The returned iterator can be moved to another thread
+    /// and ends when the connection is closed.
+    pub fn events(&self) -> Result<ClientEvents> {
+        let (sender, receiver) = mpsc::channel();
+        self.inner.router.subscribe(None, sender)?;
+        Ok(ClientEvents { receiver })
+    }
+
+    fn send_recv_convert_command<T>(&self, command: Command) -> Result<T>
+    where
+        T: TryFromValue,
+    {
+        T::try_from(self.send_recv_command(command)?)
+    }
+
+    fn send_recv_command(&self, command: Command) -> Result<Value> {
+        let request = Request {
+            command,
+            request_id: self.inner.router.next_request_id(),
+        };
+        let req_json = serde_json::to_vec(&request)?;
+        if log::log_enabled!(log::Level::Trace) {
+            log::trace!("sending: {}", String::from_utf8_lossy(&req_json));
+        }
+
+        let (sender, receiver) = mpsc::channel();
+        let _pending_reply = self
+            .inner
+            .router
+            .add_pending_reply(request.request_id, sender)?;
+
+        let write_result = {
+            let mut writer = self.inner.writer.lock().unwrap();
+            writer
+                .write_all(&req_json)
+                .and_then(|()| writer.write_all(b"\n"))
+                .and_then(|()| writer.flush())
+        };
+        write_result?;
+
+        let result = match self.inner.request_timeout {
+            Some(timeout) => receiver.recv_timeout(timeout),
+            None => receiver.recv().map_err(RecvTimeoutError::from),
+        };
+        match result {
+            Ok(result) => result,
+            Err(RecvTimeoutError::Timeout) => Err(Error::Timeout),
+            Err(RecvTimeoutError::Disconnected) => Err(Error::Closed),
+        }
+    }
+}
+
+fn read_loop(router: &Router, reader: impl Read) {
+    let mut reader = BufReader::new(reader);
+    let mut line = Vec::with_capacity(128);
+
+    loop {
+        match reader.read_until(b'\n', &mut line) {
+            Ok(0) => break,
+            Ok(_) => {
+                router.dispatch(&line);
+                line.clear();
+            }
+            Err(io_error) => match io_error.kind() {
+                std::io::ErrorKind::WouldBlock
+                | std::io::ErrorKind::TimedOut
+                | std::io::ErrorKind::Interrupted => continue,
+                _ => {
+                    log::error!("error while reading from mpv socket: {}", io_error);
+                    break;
+                }
+            },
+        }
+    }
+
+    router.close();
+}
+
+/// Iterator over the changes of an observed property.
+///
+/// Returned by [`MpvClient::observe_property`].
+///
+/// [`MpvClient::observe_property`]: ./struct.MpvClient.html#method.observe_property
+pub struct PropertyObserver<T> {
+    client: MpvClient,
+    observe_id: i64,
+    receiver: Receiver<Event>,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T: TryFromValue> Iterator for PropertyObserver<T> {
+    type Item = Result<T>;
+
+    fn next(&mut self) -> Option<Result<T>> {
+        loop {
+            let event = self.receiver.recv().ok()?;
+            if let Some(data) = property_change_data(event) {
+                return Some(T::try_from(data));
+            }
+        }
+    }
+}
+
+impl<T> Drop for PropertyObserver<T> {
+    fn drop(&mut self) {
+        self.client.inner.router.unsubscribe(self.observe_id);
+        if self.client.inner.router.is_closed() {
+            return;
+        }
+        if let Err(error) = self
+            .client
+            .send_recv_command(Command::UnobserveProperty(self.observe_id))
+        {
+            log::error!("error while dropping property observer: {}", error);
+        }
+    }
+}
+
+/// Iterator over all events of an [`MpvClient`].
+///
+/// Returned by [`MpvClient::events`].
+///
+/// [`MpvClient`]: ./struct.MpvClient.html
+/// [`MpvClient::events`]: ./struct.MpvClient.html#method.events
+pub struct ClientEvents {
+    receiver: Receiver<Event>,
+}
+
+impl Iterator for ClientEvents {
+    type Item = Event;
+
+    fn next(&mut self) -> Option<Event> {
+        self.receiver.recv().ok()
+    }
+}
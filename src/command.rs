@@ -0,0 +1,276 @@
+use std::fmt::Write;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use crate::protocol::Command;
+use crate::{MpvSocket, Result, TryFromValue, Value};
+
+/// How a file or playlist should be added to the playlist.
+///
+/// Used by [`MpvSocket::loadfile`] and [`MpvSocket::loadlist`].
+///
+/// [`MpvSocket::loadfile`]: ./struct.MpvSocket.html#method.loadfile
+/// [`MpvSocket::loadlist`]: ./struct.MpvSocket.html#method.loadlist
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Default)]
+pub enum LoadFileMode {
+    /// Stop playback of the current file, and play the new file immediately.
+    #[default]
+    Replace,
+    /// Append the file to the playlist.
+    Append,
+    /// Append the file, and if nothing is currently playing, start playback.
+    ///
+    /// (Always starts with the added file, even if the playlist was not empty before running this command.)
+    AppendPlay,
+    /// Insert the file into the playlist, directly after the current entry.
+    InsertNext,
+    /// Insert the file next, and if nothing is currently playing, start playback.
+    ///
+    /// (Always starts with the added file, even if the playlist was not empty before running this command.)
+    InsertNextPlay,
+    /// Insert the file into the playlist, at the given index.
+    ///
+    /// If the index is less than 0 or greater than the size of the playlist,
+    /// the entry will be added to the end of the playlist.
+    InsertAt(i64),
+    /// Insert the file at the given index, and if nothing is currently playing, start playback.
+    ///
+    /// (Always starts with the added file, even if the playlist was not empty before running this command.)
+    InsertAtPlay(i64),
+}
+
+impl LoadFileMode {
+    pub(crate) fn flag(self) -> &'static str {
+        match self {
+            LoadFileMode::Replace => "replace",
+            LoadFileMode::Append => "append",
+            LoadFileMode::AppendPlay => "append-play",
+            LoadFileMode::InsertNext => "insert-next",
+            LoadFileMode::InsertNextPlay => "insert-next-play",
+            LoadFileMode::InsertAt(..) => "insert-at",
+            LoadFileMode::InsertAtPlay(..) => "insert-at-play",
+        }
+    }
+
+    pub(crate) fn index(self) -> Option<i64> {
+        match self {
+            LoadFileMode::InsertAt(index) | LoadFileMode::InsertAtPlay(index) => Some(index),
+            _ => None,
+        }
+    }
+}
+
+/// How the target of a seek is interpreted.
+///
+/// Used by [`MpvSocket::seek`].
+///
+/// [`MpvSocket::seek`]: ./struct.MpvSocket.html#method.seek
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Default)]
+pub enum SeekMode {
+    /// Seek relative to current position (a negative value seeks backwards).
+    #[default]
+    Relative,
+    /// Seek to a given time (a negative value starts from the end of the file).
+    Absolute,
+    /// Seek to a given percent position.
+    AbsolutePercent,
+    /// Seek relative to current position in percent.
+    RelativePercent,
+}
+
+impl SeekMode {
+    pub(crate) fn flag(self) -> &'static str {
+        match self {
+            SeekMode::Relative => "relative",
+            SeekMode::Absolute => "absolute",
+            SeekMode::AbsolutePercent => "absolute-percent",
+            SeekMode::RelativePercent => "relative-percent",
+        }
+    }
+}
+
+/// How precise a seek should be.
+///
+/// Used by [`MpvSocket::seek_with_precision`].
+///
+/// [`MpvSocket::seek_with_precision`]: ./struct.MpvSocket.html#method.seek_with_precision
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Default)]
+pub enum SeekPrecision {
+    /// Use the default behavior of mpv.
+    ///
+    /// This is controlled by the `--hr-seek` option.
+    #[default]
+    Default,
+    /// Always restart playback at keyframe boundaries (fast).
+    Keyframes,
+    /// Always do exact/hr/precise seeks (slow).
+    Exact,
+}
+
+impl SeekPrecision {
+    pub(crate) fn flag(self) -> Option<&'static str> {
+        match self {
+            SeekPrecision::Default => None,
+            SeekPrecision::Keyframes => Some("keyframes"),
+            SeekPrecision::Exact => Some("exact"),
+        }
+    }
+}
+
+pub(crate) type DroppedAsyncCommands = Arc<Mutex<Vec<i64>>>;
+
+/// Handle to a command which runs asynchronously in mpv.
+///
+/// Returned by [`MpvSocket::command_async`].
+/// The connection can be used for other commands and events while the command runs,
+/// its reply is kept until [`wait`] is called.
+///
+/// When the handle is dropped without waiting, the command keeps running,
+/// but its reply is discarded.
+///
+/// [`MpvSocket::command_async`]: ./struct.MpvSocket.html#method.command_async
+/// [`wait`]: #method.wait
+#[derive(Debug)]
+#[must_use = "the reply of an async command is discarded when the handle is dropped"]
+pub struct AsyncCommand {
+    id: i64,
+    dropped: DroppedAsyncCommands,
+}
+
+impl AsyncCommand {
+    pub(crate) fn new(id: i64, dropped: DroppedAsyncCommands) -> AsyncCommand {
+        AsyncCommand { id, dropped }
+    }
+
+    /// The request id mpv uses for this command.
+    pub fn id(&self) -> i64 {
+        self.id
+    }
+
+    /// Block until the command has finished and return its result.
+    ///
+    /// Waits as long as for the reply of any other request,
+    /// see [`MpvSocketBuilder::request_timeout`] and [`MpvSocketBuilder::read_timeout`].
+    /// Use [`wait_timeout`] for commands which take longer.
+    ///
+    /// [`MpvSocketBuilder::request_timeout`]: ./struct.MpvSocketBuilder.html#method.request_timeout
+    /// [`MpvSocketBuilder::read_timeout`]: ./struct.MpvSocketBuilder.html#method.read_timeout
+    /// [`wait_timeout`]: #method.wait_timeout
+    pub fn wait<T>(self, mpv: &mut MpvSocket) -> Result<T>
+    where
+        T: TryFromValue,
+    {
+        T::try_from(mpv.wait_async_command(self.id, None)?)
+    }
+
+    /// Block until the command has finished and return its result,
+    /// or fail with [`Error::Timeout`] if it does not finish within the given timeout.
+    ///
+    /// Waiting for the command requires a read timeout of the socket,
+    /// otherwise a read blocks until mpv sends data.
+    ///
+    /// The handle is kept when the timeout passes, so waiting can be continued later.
+    /// Once the command has finished, further waits fail.
+    ///
+    /// [`Error::Timeout`]: ./enum.Error.html#variant.Timeout
+    pub fn wait_timeout<T>(&mut self, mpv: &mut MpvSocket, timeout: Duration) -> Result<T>
+    where
+        T: TryFromValue,
+    {
+        T::try_from(mpv.wait_async_command(self.id, Some(timeout))?)
+    }
+
+    /// Ask mpv to abort the command.
+    ///
+    /// Aborting is best effort: not all commands can be aborted,
+    /// and the command might finish before the request arrives.
+    /// Either way the command still has a reply, which [`wait`] returns.
+    ///
+    /// [`wait`]: #method.wait
+    pub fn abort(&self, mpv: &mut MpvSocket) -> Result<()> {
+        mpv.send_recv_command(Command::AbortAsync(self.id))?;
+        Ok(())
+    }
+}
+
+impl Drop for AsyncCommand {
+    fn drop(&mut self) {
+        if let Ok(mut dropped) = self.dropped.lock() {
+            dropped.push(self.id);
+        }
+    }
+}
+
+/// An input command with named arguments.
+///
+/// Named arguments are more robust than positional arguments
+/// for commands with many optional parameters,
+/// like `loadfile`, `sub-add`, `screenshot-to-file` or `overlay-add`.
+/// Arguments which are not given use their default value.
+///
+/// Run with [`MpvSocket::command_named`].
+///
+/// # Example
+///
+/// ```
+/// use mpv_socket::NamedCommand;
+///
+/// let command = NamedCommand::new("sub-add")
+///     .arg("url", "subtitles.srt")
+///     .arg("flags", "cached")
+///     .arg("lang", "eng");
+/// ```
+///
+/// [`MpvSocket::command_named`]: ./struct.MpvSocket.html#method.command_named
+#[derive(Debug, Clone, PartialEq)]
+pub struct NamedCommand {
+    pub(crate) name: String,
+    pub(crate) args: Vec<(String, Value)>,
+}
+
+impl NamedCommand {
+    /// Create a command with the given name and no arguments.
+    pub fn new(name: &str) -> NamedCommand {
+        NamedCommand {
+            name: name.to_owned(),
+            args: Vec::new(),
+        }
+    }
+
+    /// Add the argument with the given name.
+    ///
+    /// Setting the same argument again replaces its previous value.
+    /// The argument `name` is reserved for the name of the command and is ignored.
+    pub fn arg(mut self, name: &str, value: impl Into<Value>) -> NamedCommand {
+        if name == "name" {
+            log::warn!("ignoring reserved argument `name` of command {}", self.name);
+            return self;
+        }
+        let value = value.into();
+        match self.args.iter_mut().find(|(arg_name, _)| arg_name == name) {
+            Some((_, arg_value)) => *arg_value = value,
+            None => self.args.push((name.to_owned(), value)),
+        }
+        self
+    }
+}
+
+/// Formats per-file options as a `key=value` list as expected by `loadfile`.
+///
+/// Values containing characters with a special meaning in option lists
+/// are quoted with the `%length%value` syntax.
+pub(crate) fn format_options(options: &[(String, String)]) -> Value {
+    let mut formatted = String::new();
+    for (key, value) in options {
+        if !formatted.is_empty() {
+            formatted.push(',');
+        }
+        formatted.push_str(key);
+        formatted.push('=');
+        if value.contains([',', '%', '"', '\'', '[', ']']) {
+            let _ = write!(formatted, "%{}%", value.len());
+        }
+        formatted.push_str(value);
+    }
+    Value::from(formatted)
+}
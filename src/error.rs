@@ -1,11 +1,212 @@
-/// Simple opaque error type for this library.
-///
-/// The real cause can be downcasted if necessary.
-pub type Error = Box<dyn std::error::Error + Send + Sync>;
+use std::fmt;
+
+use crate::Value;
+
+/// Error type for this library.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum Error {
+    /// Reading from or writing to the socket failed.
+    Io(std::io::Error),
+    /// A message could not be serialized or deserialized.
+    Json(serde_json::Error),
+    /// The connection to mpv is closed.
+    Closed,
+    /// mpv did not respond in time.
+    Timeout,
+    /// A value does not have the expected type.
+    TypeMismatch {
+        /// Name of the expected type.
+        expected: &'static str,
+        /// The actual value.
+        got: Value,
+    },
+    /// mpv responded with an error.
+    Mpv(MpvErrorCode),
+    /// mpv sent a response which does not fit the request.
+    UnexpectedResponse(String),
+    /// Any other error, like errors returned by hook or client message handlers.
+    Other(Box<dyn std::error::Error + Send + Sync>),
+}
 
 /// Type alias for `Result<T, mpv_socket::Error>`.
 pub type Result<T> = std::result::Result<T, Error>;
 
+impl Error {
+    pub(crate) fn type_mismatch(expected: &'static str, got: Value) -> Error {
+        Error::TypeMismatch { expected, got }
+    }
+
+    pub(crate) fn other(message: impl Into<String>) -> Error {
+        Error::Other(message.into().into())
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Io(error) => write!(f, "mpv socket I/O error: {}", error),
+            Error::Json(error) => write!(f, "invalid mpv message: {}", error),
+            Error::Closed => f.write_str("mpv socket is closed"),
+            Error::Timeout => f.write_str("timed out waiting for mpv"),
+            Error::TypeMismatch { expected, got } => {
+                write!(f, "expected {}, but got: {:?}", expected, got)
+            }
+            Error::Mpv(code) => write!(f, "mpv error response: {}", code),
+            Error::UnexpectedResponse(response) => {
+                write!(f, "unexpected mpv response: {}", response)
+            }
+            Error::Other(error) => error.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io(error) => Some(error),
+            Error::Json(error) => Some(error),
+            Error::Other(error) => Some(error.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for Error {
+    fn from(error: std::io::Error) -> Error {
+        match error.kind() {
+            std::io::ErrorKind::WouldBlock | std::io::ErrorKind::TimedOut => Error::Timeout,
+            _ => Error::Io(error),
+        }
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(error: serde_json::Error) -> Error {
+        Error::Json(error)
+    }
+}
+
+impl From<MpvErrorCode> for Error {
+    fn from(code: MpvErrorCode) -> Error {
+        Error::Mpv(code)
+    }
+}
+
+/// Errors mpv responds with.
+///
+/// Official documentation: [https://mpv.io/manual/master/#json-ipc](https://mpv.io/manual/master/#json-ipc)
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+#[non_exhaustive]
+pub enum MpvErrorCode {
+    /// The event ringbuffer is full.
+    EventQueueFull,
+    /// Memory allocation failed.
+    NoMem,
+    /// The mpv core wasn't configured and initialized yet.
+    Uninitialized,
+    /// Generic catch-all error if a parameter is set to an invalid or unsupported value.
+    InvalidParameter,
+    /// Trying to set an option that doesn't exist.
+    OptionNotFound,
+    /// Trying to set an option using an unsupported format.
+    OptionFormat,
+    /// Setting the option failed.
+    OptionError,
+    /// The accessed property doesn't exist.
+    PropertyNotFound,
+    /// Trying to set or get a property using an unsupported format.
+    PropertyFormat,
+    /// The property exists, but is not available.
+    ///
+    /// This usually happens when the associated subsystem is not active,
+    /// e.g. querying audio parameters while audio is disabled.
+    PropertyUnavailable,
+    /// Error setting or getting a property.
+    PropertyError,
+    /// General error when running a command.
+    Command,
+    /// Generic error on loading (usually used with the reason of an end-file event).
+    LoadingFailed,
+    /// Initializing the audio output failed.
+    AoInitFailed,
+    /// Initializing the video output failed.
+    VoInitFailed,
+    /// There was no audio or video data to play.
+    NothingToPlay,
+    /// The file format could not be determined, or the file was too broken to open it.
+    UnknownFormat,
+    /// Generic error for signaling that certain system requirements are not fulfilled.
+    Unsupported,
+    /// The API function which was called is a stub only.
+    NotImplemented,
+    /// Unspecified error.
+    Generic,
+    /// An error string unknown to this library.
+    Other(String),
+}
+
+impl MpvErrorCode {
+    pub(crate) fn from_str(error: &str) -> MpvErrorCode {
+        match error {
+            "event queue full" => MpvErrorCode::EventQueueFull,
+            "memory allocation failed" => MpvErrorCode::NoMem,
+            "core not uninitialized" => MpvErrorCode::Uninitialized,
+            "invalid parameter" => MpvErrorCode::InvalidParameter,
+            "option not found" => MpvErrorCode::OptionNotFound,
+            "unsupported format for accessing option" => MpvErrorCode::OptionFormat,
+            "error setting option" => MpvErrorCode::OptionError,
+            "property not found" => MpvErrorCode::PropertyNotFound,
+            "unsupported format for accessing property" => MpvErrorCode::PropertyFormat,
+            "property unavailable" => MpvErrorCode::PropertyUnavailable,
+            "error accessing property" => MpvErrorCode::PropertyError,
+            "error running command" => MpvErrorCode::Command,
+            "loading failed" => MpvErrorCode::LoadingFailed,
+            "audio output initialization failed" => MpvErrorCode::AoInitFailed,
+            "video output initialization failed" => MpvErrorCode::VoInitFailed,
+            "no audio or video data played" => MpvErrorCode::NothingToPlay,
+            "unrecognized file format" => MpvErrorCode::UnknownFormat,
+            "not supported" => MpvErrorCode::Unsupported,
+            "operation not implemented" => MpvErrorCode::NotImplemented,
+            "something happened" => MpvErrorCode::Generic,
+            error => MpvErrorCode::Other(error.to_owned()),
+        }
+    }
+
+    /// The error string as sent by mpv.
+    pub fn as_str(&self) -> &str {
+        match self {
+            MpvErrorCode::EventQueueFull => "event queue full",
+            MpvErrorCode::NoMem => "memory allocation failed",
+            MpvErrorCode::Uninitialized => "core not uninitialized",
+            MpvErrorCode::InvalidParameter => "invalid parameter",
+            MpvErrorCode::OptionNotFound => "option not found",
+            MpvErrorCode::OptionFormat => "unsupported format for accessing option",
+            MpvErrorCode::OptionError => "error setting option",
+            MpvErrorCode::PropertyNotFound => "property not found",
+            MpvErrorCode::PropertyFormat => "unsupported format for accessing property",
+            MpvErrorCode::PropertyUnavailable => "property unavailable",
+            MpvErrorCode::PropertyError => "error accessing property",
+            MpvErrorCode::Command => "error running command",
+            MpvErrorCode::LoadingFailed => "loading failed",
+            MpvErrorCode::AoInitFailed => "audio output initialization failed",
+            MpvErrorCode::VoInitFailed => "video output initialization failed",
+            MpvErrorCode::NothingToPlay => "no audio or video data played",
+            MpvErrorCode::UnknownFormat => "unrecognized file format",
+            MpvErrorCode::Unsupported => "not supported",
+            MpvErrorCode::NotImplemented => "operation not implemented",
+            MpvErrorCode::Generic => "something happened",
+            MpvErrorCode::Other(error) => error,
+        }
+    }
+}
+
+impl fmt::Display for MpvErrorCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
 /// All pipe instances are busy.
 #[cfg(target_os = "windows")]
 pub(crate) const ERROR_PIPE_BUSY: i32 = 231;
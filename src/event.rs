@@ -4,13 +4,15 @@
 
 #![allow(deprecated)]
 
+use std::fmt;
+
 use serde::Deserialize;
 use serde_json::Value;
 
-use crate::Property;
+use crate::{MpvSocket, PropertyName, Result};
 
 /// Mpv event variants.
-#[derive(Debug, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Deserialize, PartialEq)]
 #[serde(tag = "event", rename_all = "kebab-case")]
 pub enum Event {
     /// Happens after a property change for observed properties.
@@ -58,10 +60,13 @@ pub enum Event {
     // See C API.
     #[doc(hidden)]
     CommandReply(Value),
-    /// Lua and possibly other backends treat this specially
-    /// and may not pass the actual event to the user.
-    #[doc(hidden)]
-    ClientMessage(Value),
+    /// Happens when a client or script sends a message with the `script-message` command
+    /// (or `script-message-to` with this client as target).
+    ///
+    /// See [`MpvSocket::on_client_message`] to route messages to handlers.
+    ///
+    /// [`MpvSocket::on_client_message`]: ../struct.MpvSocket.html#method.on_client_message
+    ClientMessage(ClientMessageEvent),
     /// Happens on video output or filter reconfig.
     VideoReconfig,
     /// Happens on audio output or filter reconfig.
@@ -106,13 +111,184 @@ pub enum Event {
     Other,
 }
 
+impl Event {
+    /// Returns the kind of this event.
+    ///
+    /// Returns `None` for events unknown to this library.
+    pub fn kind(&self) -> Option<EventKind> {
+        let kind = match self {
+            Event::PropertyChange(..) => EventKind::PropertyChange,
+            Event::StartFile(..) => EventKind::StartFile,
+            Event::EndFile(..) => EventKind::EndFile,
+            Event::FileLoaded => EventKind::FileLoaded,
+            Event::Seek => EventKind::Seek,
+            Event::PlaybackRestart => EventKind::PlaybackRestart,
+            Event::Shutdown => EventKind::Shutdown,
+            Event::LogMessage(..) => EventKind::LogMessage,
+            Event::Hook(..) => EventKind::Hook,
+            Event::GetPropertyReply(..) => EventKind::GetPropertyReply,
+            Event::SetPropertyReply(..) => EventKind::SetPropertyReply,
+            Event::CommandReply(..) => EventKind::CommandReply,
+            Event::ClientMessage(..) => EventKind::ClientMessage,
+            Event::VideoReconfig => EventKind::VideoReconfig,
+            Event::AudioReconfig => EventKind::AudioReconfig,
+            Event::TracksChanged => EventKind::TracksChanged,
+            Event::TrackSwitched => EventKind::TrackSwitched,
+            Event::Pause => EventKind::Pause,
+            Event::Unpause => EventKind::Unpause,
+            Event::MetadataUpdate => EventKind::MetadataUpdate,
+            Event::Idle => EventKind::Idle,
+            Event::Tick => EventKind::Tick,
+            Event::ChapterChange => EventKind::ChapterChange,
+            Event::__NonExhaustive | Event::Other => return None,
+        };
+        Some(kind)
+    }
+}
+
+/// Kind of an [`Event`], without its payload.
+///
+/// Used to enable or disable events with [`MpvSocket::enable_event`]
+/// and [`MpvSocket::disable_event`].
+///
+/// [`Event`]: ./enum.Event.html
+/// [`MpvSocket::enable_event`]: ../struct.MpvSocket.html#method.enable_event
+/// [`MpvSocket::disable_event`]: ../struct.MpvSocket.html#method.disable_event
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum EventKind {
+    /// All events.
+    All,
+    /// See [`Event::PropertyChange`].
+    ///
+    /// [`Event::PropertyChange`]: ./enum.Event.html#variant.PropertyChange
+    PropertyChange,
+    /// See [`Event::StartFile`].
+    ///
+    /// [`Event::StartFile`]: ./enum.Event.html#variant.StartFile
+    StartFile,
+    /// See [`Event::EndFile`].
+    ///
+    /// [`Event::EndFile`]: ./enum.Event.html#variant.EndFile
+    EndFile,
+    /// See [`Event::FileLoaded`].
+    ///
+    /// [`Event::FileLoaded`]: ./enum.Event.html#variant.FileLoaded
+    FileLoaded,
+    /// See [`Event::Seek`].
+    ///
+    /// [`Event::Seek`]: ./enum.Event.html#variant.Seek
+    Seek,
+    /// See [`Event::PlaybackRestart`].
+    ///
+    /// [`Event::PlaybackRestart`]: ./enum.Event.html#variant.PlaybackRestart
+    PlaybackRestart,
+    /// See [`Event::Shutdown`].
+    ///
+    /// [`Event::Shutdown`]: ./enum.Event.html#variant.Shutdown
+    Shutdown,
+    /// See [`Event::LogMessage`].
+    ///
+    /// [`Event::LogMessage`]: ./enum.Event.html#variant.LogMessage
+    LogMessage,
+    /// See [`Event::Hook`].
+    ///
+    /// [`Event::Hook`]: ./enum.Event.html#variant.Hook
+    Hook,
+    /// See C API.
+    #[doc(hidden)]
+    GetPropertyReply,
+    /// See C API.
+    #[doc(hidden)]
+    SetPropertyReply,
+    /// See C API.
+    #[doc(hidden)]
+    CommandReply,
+    /// See [`Event::ClientMessage`].
+    ///
+    /// [`Event::ClientMessage`]: ./enum.Event.html#variant.ClientMessage
+    ClientMessage,
+    /// See [`Event::VideoReconfig`].
+    ///
+    /// [`Event::VideoReconfig`]: ./enum.Event.html#variant.VideoReconfig
+    VideoReconfig,
+    /// See [`Event::AudioReconfig`].
+    ///
+    /// [`Event::AudioReconfig`]: ./enum.Event.html#variant.AudioReconfig
+    AudioReconfig,
+    /// Deprecated event, which may still be sent by mpv and can therefore be disabled.
+    TracksChanged,
+    /// Deprecated event, which may still be sent by mpv and can therefore be disabled.
+    TrackSwitched,
+    /// Deprecated event, which may still be sent by mpv and can therefore be disabled.
+    Pause,
+    /// Deprecated event, which may still be sent by mpv and can therefore be disabled.
+    Unpause,
+    /// Deprecated event, which may still be sent by mpv and can therefore be disabled.
+    MetadataUpdate,
+    /// Deprecated event, which may still be sent by mpv and can therefore be disabled.
+    Idle,
+    /// Deprecated event, which may still be sent by mpv and can therefore be disabled.
+    Tick,
+    /// Deprecated event, which may still be sent by mpv and can therefore be disabled.
+    ChapterChange,
+}
+
+impl EventKind {
+    /// Returns the name of the event as used by mpv.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            EventKind::All => "all",
+            EventKind::PropertyChange => "property-change",
+            EventKind::StartFile => "start-file",
+            EventKind::EndFile => "end-file",
+            EventKind::FileLoaded => "file-loaded",
+            EventKind::Seek => "seek",
+            EventKind::PlaybackRestart => "playback-restart",
+            EventKind::Shutdown => "shutdown",
+            EventKind::LogMessage => "log-message",
+            EventKind::Hook => "hook",
+            EventKind::GetPropertyReply => "get-property-reply",
+            EventKind::SetPropertyReply => "set-property-reply",
+            EventKind::CommandReply => "command-reply",
+            EventKind::ClientMessage => "client-message",
+            EventKind::VideoReconfig => "video-reconfig",
+            EventKind::AudioReconfig => "audio-reconfig",
+            EventKind::TracksChanged => "tracks-changed",
+            EventKind::TrackSwitched => "track-switched",
+            EventKind::Pause => "pause",
+            EventKind::Unpause => "unpause",
+            EventKind::MetadataUpdate => "metadata-update",
+            EventKind::Idle => "idle",
+            EventKind::Tick => "tick",
+            EventKind::ChapterChange => "chapter-change",
+        }
+    }
+}
+
+impl From<EventKind> for Value {
+    fn from(kind: EventKind) -> Self {
+        Value::from(kind.as_str())
+    }
+}
+
+impl fmt::Display for EventKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
 /// Payload for [`Event::PropertyChange`].
 ///
 /// [`Event::PropertyChange`]: ./enum.Event.html#variant.PropertyChange
-#[derive(Debug, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Deserialize, PartialEq)]
 pub struct PropertyChangeEvent {
     /// The property whose value was changed.
-    pub name: Property,
+    ///
+    /// Properties unknown to this library and sub-properties
+    /// are reported as [`PropertyName::Custom`].
+    ///
+    /// [`PropertyName::Custom`]: ../enum.PropertyName.html#variant.Custom
+    pub name: PropertyName,
     /// New property data.
     ///
     /// The type usually is the value type of the property,
@@ -127,7 +303,7 @@ pub struct PropertyChangeEvent {
 /// Payload for [`Event::StartFile`].
 ///
 /// [`Event::StartFile`]: ./enum.Event.html#variant.StartFile
-#[derive(Debug, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Deserialize, PartialEq)]
 pub struct StartFileEvent {
     /// Playlist entry ID of the file being loaded now.
     pub playlist_entry_id: Option<i64>,
@@ -136,7 +312,7 @@ pub struct StartFileEvent {
 /// Payload for [`Event::EndFile`].
 ///
 /// [`Event::EndFile`]: ./enum.Event.html#variant.EndFile
-#[derive(Debug, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Deserialize, PartialEq)]
 pub struct EndFileEvent {
     /// Why the playback has ended.
     pub reason: Option<Reason>,
@@ -209,25 +385,32 @@ pub enum Reason {
     ///
     /// (Likewise, it could happen that your script gets reason strings
     /// that did not exist yet at the time your script was written.)
+    ///
+    /// Reason strings unknown to this library are reported as this variant as well.
+    #[serde(other)]
     Unknown,
 }
 
 /// Payload for [`Event::LogMessage`].
 ///
 /// [`Event::LogMessage`]: ./enum.Event.html#variant.LogMessage
-#[derive(Debug, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Deserialize, PartialEq)]
 pub struct LogMessageEvent {
     /// The module prefix, identifies the sender of the message.
     ///
     /// This is what the terminal player puts in front of the message text
     /// when using the `--v` option, and is also what is used for `--msg-level`.
-    prefix: String,
+    pub prefix: String,
     /// The log level as string.
     ///
     /// See `msg.log` for possible log level names.
     /// Note that later versions of mpv might add new levels
     /// or remove (undocumented) existing ones.
-    level: String,
+    ///
+    /// Use [`log_level`] to get the typed log level.
+    ///
+    /// [`log_level`]: #method.log_level
+    pub level: String,
     /// The log message.
     ///
     /// The text will end with a newline character.
@@ -235,16 +418,172 @@ pub struct LogMessageEvent {
     ///
     /// Keep in mind that these messages are meant to be hints for humans.
     /// You should not parse them, and prefix/level/text of messages might change any time.
-    text: String,
+    pub text: String,
+}
+
+impl LogMessageEvent {
+    /// Returns the typed log level of this message.
+    ///
+    /// Returns `None` for log levels unknown to this library.
+    pub fn log_level(&self) -> Option<MpvLogLevel> {
+        MpvLogLevel::from_str(&self.level)
+    }
+
+    /// Re-emits this message through the [`log`] crate.
+    ///
+    /// The `prefix` of the message is used as log target,
+    /// so mpv's log messages can be filtered like the ones of other modules,
+    /// e.g. with `RUST_LOG=cplayer=debug` when using `env_logger`.
+    ///
+    /// Messages with unknown log levels are logged with [`log::Level::Info`].
+    ///
+    /// # Example:
+    ///
+    /// ```no_run
+    /// use mpv_socket::event::{Event, MpvLogLevel};
+    /// use mpv_socket::{Error, MpvSocket};
+    ///
+    /// fn main() -> Result<(), Error> {
+    ///     let mut mpv_socket = MpvSocket::connect("/tmp/mpv-socket")?;
+    ///     mpv_socket.request_log_messages(MpvLogLevel::Info)?;
+    ///
+    ///     for result in mpv_socket.events() {
+    ///         if let Event::LogMessage(log_message) = result? {
+    ///             log_message.forward_to_log();
+    ///         }
+    ///     }
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// [`log`]: https://docs.rs/log
+    /// [`log::Level::Info`]: https://docs.rs/log/0.4/log/enum.Level.html#variant.Info
+    pub fn forward_to_log(&self) {
+        let level = match self.log_level() {
+            Some(level) => match level.to_log_level() {
+                Some(level) => level,
+                None => return,
+            },
+            None => log::Level::Info,
+        };
+
+        log::log!(target: &self.prefix, level, "{}", self.text.trim_end());
+    }
+}
+
+/// Log level of mpv log messages.
+///
+/// Used by [`MpvSocket::request_log_messages`].
+///
+/// [`MpvSocket::request_log_messages`]: ../struct.MpvSocket.html#method.request_log_messages
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum MpvLogLevel {
+    /// Disable logging completely.
+    No,
+    /// Critical/aborting errors.
+    Fatal,
+    /// Simple errors.
+    Error,
+    /// Possible problems.
+    Warn,
+    /// Informational message.
+    Info,
+    /// Status messages, like the status line of the terminal player.
+    Status,
+    /// Noisy informational message.
+    V,
+    /// Very noisy technical information.
+    Debug,
+    /// Extremely noisy.
+    Trace,
+}
+
+impl MpvLogLevel {
+    /// Returns the name of the log level as used by mpv.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            MpvLogLevel::No => "no",
+            MpvLogLevel::Fatal => "fatal",
+            MpvLogLevel::Error => "error",
+            MpvLogLevel::Warn => "warn",
+            MpvLogLevel::Info => "info",
+            MpvLogLevel::Status => "status",
+            MpvLogLevel::V => "v",
+            MpvLogLevel::Debug => "debug",
+            MpvLogLevel::Trace => "trace",
+        }
+    }
+
+    /// Returns the matching [`log::Level`], or `None` for [`MpvLogLevel::No`].
+    ///
+    /// [`log::Level`]: https://docs.rs/log/0.4/log/enum.Level.html
+    /// [`MpvLogLevel::No`]: #variant.No
+    pub fn to_log_level(self) -> Option<log::Level> {
+        match self {
+            MpvLogLevel::No => None,
+            MpvLogLevel::Fatal | MpvLogLevel::Error => Some(log::Level::Error),
+            MpvLogLevel::Warn => Some(log::Level::Warn),
+            MpvLogLevel::Info | MpvLogLevel::Status => Some(log::Level::Info),
+            MpvLogLevel::V | MpvLogLevel::Debug => Some(log::Level::Debug),
+            MpvLogLevel::Trace => Some(log::Level::Trace),
+        }
+    }
+
+    fn from_str(level: &str) -> Option<MpvLogLevel> {
+        let level = match level {
+            "no" => MpvLogLevel::No,
+            "fatal" => MpvLogLevel::Fatal,
+            "error" => MpvLogLevel::Error,
+            "warn" => MpvLogLevel::Warn,
+            "info" => MpvLogLevel::Info,
+            "status" => MpvLogLevel::Status,
+            "v" => MpvLogLevel::V,
+            "debug" => MpvLogLevel::Debug,
+            "trace" => MpvLogLevel::Trace,
+            _ => return None,
+        };
+        Some(level)
+    }
 }
 
+impl From<MpvLogLevel> for Value {
+    fn from(level: MpvLogLevel) -> Self {
+        Value::from(level.as_str())
+    }
+}
+
+/// Payload for [`Event::ClientMessage`].
+///
+/// [`Event::ClientMessage`]: ./enum.Event.html#variant.ClientMessage
+#[derive(Debug, Clone, Deserialize, PartialEq)]
+pub struct ClientMessageEvent {
+    /// The arguments of the message.
+    ///
+    /// By convention, the first argument is the name of the message.
+    #[serde(default)]
+    pub args: Vec<String>,
+}
+
+pub(crate) type ClientMessageHandler = Box<dyn FnMut(&mut MpvSocket, &[String]) -> Result<()>>;
+
 /// Payload for [`Event::Hook`].
 ///
 /// [`Event::Hook`]: ./enum.Event.html#variant.Hook
-#[derive(Debug, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Deserialize, PartialEq)]
 pub struct HookEvent {
-    /// ID to pass to `mpv_hook_continue()`.
+    /// ID the hook was registered with.
+    ///
+    /// This is the `id` passed to [`MpvSocket::hook_add`].
+    ///
+    /// [`MpvSocket::hook_add`]: ../struct.MpvSocket.html#method.hook_add
+    #[serde(skip)]
+    pub id: i64,
+    /// ID to pass to [`MpvSocket::hook_ack`].
+    ///
+    /// Hooks registered with [`MpvSocket::add_hook`] are acknowledged automatically.
     ///
-    /// The Lua scripting wrapper provides a better API around this with `mp.add_hook()`.
-    hook_id: Value,
+    /// [`MpvSocket::hook_ack`]: ../struct.MpvSocket.html#method.hook_ack
+    /// [`MpvSocket::add_hook`]: ../struct.MpvSocket.html#method.add_hook
+    pub hook_id: u64,
 }
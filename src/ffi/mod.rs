@@ -73,10 +73,9 @@ ffi_fn! {
         context: *mut libc::c_void,
     ) -> *mut mpv_socket_error {
         let socket = unsafe { &mut (*socket).0 };
-        let property_str = unsafe { CStr::from_ptr(property) }.to_str().unwrap();
-        let property = match serde_json::from_str(&format!("\"{}\"", property_str)) {
-            Ok(property) => property,
-            Err(error) => return ffi_error!("invalid property \"{}\": {}", property_str, error),
+        let property = match unsafe { CStr::from_ptr(property) }.to_str() {
+            Ok(property) => crate::PropertyName::from(property),
+            Err(error) => return ffi_error!("invalid property: {}", error),
         };
 
         let iter = match socket.observe_property(property) {
@@ -0,0 +1,54 @@
+use crate::{MpvSocket, Result, Value};
+
+/// Hooks which can be registered with [`MpvSocket::add_hook`].
+///
+/// Hooks are synchronous events between player core and a client,
+/// the player waits until the client has handled the hook.
+///
+/// Official documentation: [https://mpv.io/manual/master/#hooks](https://mpv.io/manual/master/#hooks)
+///
+/// [`MpvSocket::add_hook`]: ./struct.MpvSocket.html#method.add_hook
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Hook {
+    /// Called when a file is to be opened, before anything is actually done.
+    ///
+    /// For example, you could read and write the `stream-open-filename` property
+    /// to redirect an URL to something else,
+    /// or set per-file options with `file-local-options`.
+    OnLoad,
+    /// Called after a file has been opened, but failed to load.
+    ///
+    /// This can be used to provide a fallback in case native demuxers failed to recognize the file,
+    /// instead of always running before the native demuxers like `on_load`.
+    OnLoadFail,
+    /// Called after a file has been opened, and before tracks are selected and decoders are created.
+    OnPreloaded,
+    /// Run before closing a file, and before actually uninitializing everything.
+    OnUnload,
+    /// Run before a `start-file` event is sent.
+    OnBeforeStartFile,
+    /// Run after an `end-file` event.
+    OnAfterEndFile,
+}
+
+impl Hook {
+    /// Returns the name of the hook as used by mpv.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Hook::OnLoad => "on_load",
+            Hook::OnLoadFail => "on_load_fail",
+            Hook::OnPreloaded => "on_preloaded",
+            Hook::OnUnload => "on_unload",
+            Hook::OnBeforeStartFile => "on_before_start_file",
+            Hook::OnAfterEndFile => "on_after_end_file",
+        }
+    }
+}
+
+impl From<Hook> for Value {
+    fn from(hook: Hook) -> Self {
+        Value::from(hook.as_str())
+    }
+}
+
+pub(crate) type HookHandler = Box<dyn FnMut(&mut MpvSocket) -> Result<()>>;
@@ -33,22 +33,58 @@
 //! }
 //! ```
 
+use std::collections::{HashMap, VecDeque};
 use std::io::{BufRead, BufReader, Read, Write};
 use std::num::Wrapping;
 use std::path::Path;
-
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+pub use crate::batch::*;
+pub use crate::builder::*;
+pub use crate::chapter::*;
+#[cfg(unix)]
+pub use crate::client::*;
+pub use crate::command::*;
 pub use crate::error::*;
-use crate::event::{Event, PropertyChangeEvent};
+use crate::event::{
+    ClientMessageEvent, ClientMessageHandler, Event, EventKind, MpvLogLevel, PropertyChangeEvent,
+};
+pub use crate::hook::*;
+pub use crate::observation::*;
+pub use crate::playlist::*;
+#[cfg(all(unix, feature = "process"))]
+pub use crate::process::*;
 pub use crate::property::*;
 use crate::protocol::EventResponse;
-use crate::protocol::{Command, CommandResponse, Request};
-
+use crate::protocol::{AsyncRequest, Command, CommandResponse, Request};
+pub use crate::track::*;
+
+mod batch;
+mod builder;
+mod chapter;
+#[cfg(unix)]
+mod client;
+mod command;
 mod error;
 pub mod event;
+mod hook;
+#[cfg(test)]
+mod mock;
+mod observation;
+mod playlist;
+#[cfg(all(unix, feature = "process"))]
+mod process;
 mod property;
 pub(crate) mod protocol;
+#[cfg(unix)]
+mod router;
 mod serde_impl;
+mod track;
+mod transport;
 
+#[cfg(all(unix, feature = "tokio"))]
+pub mod async_client;
 #[cfg(feature = "ffi")]
 pub mod ffi;
 
@@ -68,112 +104,114 @@ impl RequestId {
     }
 
     fn advance(&mut self, num: i64) -> i64 {
-        self.0 = self.0 + Wrapping(num);
+        self.0 += Wrapping(num);
         (self.0).0
     }
 }
 
+/// Maximum number of events kept while waiting for command replies.
+///
+/// If events are never iterated, the oldest events are dropped beyond this limit.
+const MAX_PENDING_EVENTS: usize = 1024;
+
 /// Mpv socket connection.
 pub struct MpvSocket {
     socket: BufReader<Box<dyn ReadWrite>>,
     read_buf: Vec<u8>,
     last_request_id: RequestId,
+    last_hook_id: RequestId,
+    hooks: HashMap<i64, HookHandler>,
+    client_message_handlers: HashMap<String, ClientMessageHandler>,
+    async_commands: HashMap<i64, Option<CommandResponse>>,
+    dropped_async_commands: DroppedAsyncCommands,
+    /// Events received while waiting for command replies
+    /// or skipped by observation iterators.
+    pending_events: VecDeque<PendingEvent>,
+    last_pending_event: u64,
+    last_observe_id: RequestId,
+    observations: HashMap<i64, PropertyName>,
+    dropped_observations: DroppedObservations,
+    read_timeout: Option<Duration>,
+    request_timeout: Option<Duration>,
+    event_timeout: Option<Duration>,
     closed: bool,
 }
 
-#[cfg(target_os = "windows")]
 impl MpvSocket {
-    /// Connects to an mpv socket.
-    ///
-    /// The socket should be created when starting mpv via the `input-ipc-server` option, like
-    /// ```sh
-    /// mpv.exe --input-ipc-server=\\.\pipe\mpv-socket [file|url|...]
-    /// ```
-    /// in which case the given path should be `\\.\pipe\mpv-socket`.
-    ///
-    /// It is recommended to use the [`raw string literal syntax`]: `r#"\\.\pipe\mpv-socket"#`
-    ///
-    /// [`raw string literal syntax`]: https://doc.rust-lang.org/reference/tokens.html#raw-string-literals
-    pub fn connect<P: AsRef<Path>>(path: P) -> Result<MpvSocket> {
-        use std::fs::OpenOptions;
-
-        log::info!("connecting to: {}", path.as_ref().display());
-        let mut tries_left = 5u8;
-
-        let socket = loop {
-            let open_pipe_result = OpenOptions::new()
-                .read(true)
-                .write(true)
-                .open(path.as_ref());
-
-            let error = match open_pipe_result {
-                Ok(socket) => {
-                    break socket;
-                }
-                Err(error) => match error.raw_os_error() {
-                    Some(code) => match code {
-                        ERROR_PIPE_BUSY => {
-                            // On Windows the socket/pipe can only be opened
-                            // by one application and thread at the same time
-                            // and it can happen spuriously when closing/opening the connections
-                            // very often very fast, so try to guard against that.
-                            tries_left -= 1;
-                            if tries_left != 0 {
-                                std::thread::sleep(std::time::Duration::from_millis(10));
-                                continue;
-                            }
-
-                            error
-                        }
-                        _ => error,
-                    },
-                    None => error,
-                },
-            };
-
-            return Err(format!("failed to open mpv socket: {}", error).into());
-        };
-
-        Ok(MpvSocket {
-            socket: BufReader::new(Box::new(socket)),
+    fn new(socket: Box<dyn ReadWrite>) -> MpvSocket {
+        MpvSocket {
+            socket: BufReader::new(socket),
             read_buf: Vec::with_capacity(128),
             last_request_id: RequestId::new(),
+            last_hook_id: RequestId::new(),
+            hooks: HashMap::new(),
+            client_message_handlers: HashMap::new(),
+            async_commands: HashMap::new(),
+            dropped_async_commands: Arc::new(Mutex::new(Vec::new())),
+            pending_events: VecDeque::new(),
+            last_pending_event: 0,
+            last_observe_id: RequestId::new(),
+            observations: HashMap::new(),
+            dropped_observations: Arc::new(Mutex::new(Vec::new())),
+            read_timeout: None,
+            request_timeout: None,
+            event_timeout: None,
             closed: false,
-        })
+        }
     }
 }
 
-#[cfg(target_os = "linux")]
 impl MpvSocket {
     /// Connects to an mpv socket.
     ///
-    /// The socket should be created when starting mpv via the `input-ipc-server` option, like
+    /// The socket should be created when starting mpv via the `input-ipc-server` option.
+    ///
+    /// On Unix platforms like Linux, macOS and the BSDs this is a Unix domain socket:
     /// ```sh
     /// mpv --input-ipc-server=/tmp/mpv-socket [file|url|...]
     /// ```
     /// in which case the given path should be `/tmp/mpv-socket`.
+    ///
+    /// On Windows this is a named pipe:
+    /// ```sh
+    /// mpv.exe --input-ipc-server=\\.\pipe\mpv-socket [file|url|...]
+    /// ```
+    /// in which case the given path should be `\\.\pipe\mpv-socket`.
+    /// It is recommended to use the [`raw string literal syntax`]: `r#"\\.\pipe\mpv-socket"#`
+    ///
+    /// Use [`builder`] to configure timeouts and retries.
+    ///
+    /// [`raw string literal syntax`]: https://doc.rust-lang.org/reference/tokens.html#raw-string-literals
+    /// [`builder`]: #method.builder
     pub fn connect<P: AsRef<Path>>(path: P) -> Result<MpvSocket> {
-        use std::os::unix::net::UnixStream;
-        use std::time::Duration;
-
-        log::info!("connecting to: {}", path.as_ref().display());
+        MpvSocket::builder().connect(path)
+    }
 
-        let socket = UnixStream::connect(path.as_ref())
-            .map_err(|error| Error::from(format!("failed to open mpv socket: {}", error)))?;
+    /// Creates a connection over an already connected stream.
+    ///
+    /// This allows talking to mpv over any transport,
+    /// like a socket created by `socketpair` or a tunnel over SSH.
+    /// The stream is used as is, so timeouts have to be configured on the stream itself.
+    pub fn from_stream<S>(stream: S) -> MpvSocket
+    where
+        S: Read + Write + 'static,
+    {
+        MpvSocket::new(Box::new(stream))
+    }
 
-        socket
-            .set_read_timeout(Some(Duration::from_secs(10)))
-            .map_err(|error| Error::from(format!("failed to set read timeout: {}", error)))?;
-        socket
-            .set_write_timeout(Some(Duration::from_secs(10)))
-            .map_err(|error| Error::from(format!("failed to set write timeout: {}", error)))?;
+    /// Creates a connection over an already connected Unix domain socket.
+    ///
+    /// See [`from_stream`] for more information.
+    ///
+    /// [`from_stream`]: #method.from_stream
+    #[cfg(unix)]
+    pub fn from_unix_stream(stream: std::os::unix::net::UnixStream) -> MpvSocket {
+        MpvSocket::from_stream(stream)
+    }
 
-        Ok(MpvSocket {
-            socket: BufReader::new(Box::new(socket)),
-            read_buf: Vec::with_capacity(128),
-            last_request_id: RequestId::new(),
-            closed: false,
-        })
+    /// Create a builder to configure timeouts and retries of the connection.
+    pub fn builder() -> MpvSocketBuilder {
+        MpvSocketBuilder::new()
     }
 }
 
@@ -194,14 +232,19 @@ impl MpvSocket {
 
     /// Return the value of the given property.
     ///
+    /// The property can be a [`Property`] or any property path like `metadata/by-key/Artist`,
+    /// see [`PropertyName`].
+    ///
     /// See [`Properties`] for more information about properties.
     ///
     /// [`Properties`]: https://mpv.io/manual/master/#properties
-    pub fn get_property<T>(&mut self, property: Property) -> Result<T>
+    /// [`Property`]: ./enum.Property.html
+    /// [`PropertyName`]: ./enum.PropertyName.html
+    pub fn get_property<T>(&mut self, property: impl Into<PropertyName>) -> Result<T>
     where
         T: TryFromValue,
     {
-        self.send_recv_convert_command(Command::GetProperty(property))
+        self.send_recv_convert_command(Command::GetProperty(property.into()))
     }
 
     /// Set the given property to the given value.
@@ -209,8 +252,12 @@ impl MpvSocket {
     /// See [`Properties`] for more information about properties.
     ///
     /// [`Properties`]: https://mpv.io/manual/master/#properties
-    pub fn set_property(&mut self, property: Property, value: impl Into<Value>) -> Result<()> {
-        let value = self.send_recv_command(Command::SetProperty(property, value.into()))?;
+    pub fn set_property(
+        &mut self,
+        property: impl Into<PropertyName>,
+        value: impl Into<Value>,
+    ) -> Result<()> {
+        let value = self.send_recv_command(Command::SetProperty(property.into(), value.into()))?;
         debug_assert_eq!(value, Value::Null);
         Ok(())
     }
@@ -220,23 +267,28 @@ impl MpvSocket {
     /// If the given property is changed,
     /// then the iterator will return the next value.
     ///
-    /// When the returned iterator returns `None`,
-    /// the player, socket or file is closed and thus the `MpvSocket` should also be dropped.
-    /// All further calls may produce an error.
+    /// The returned iterator ends at the next [`Event::EndFile`],
+    /// that is when the current file ends,
+    /// and the socket stays usable for further calls.
+    /// It also ends when the player or socket is closed,
+    /// in which case the `MpvSocket` should also be dropped.
+    /// Other events received in the meantime, including the end-file event,
+    /// are kept and delivered by the next event iteration.
     ///
     /// See [`Properties`] for more information about properties.
     ///
     /// [`Properties`]: https://mpv.io/manual/master/#properties
+    /// [`Event::EndFile`]: ./event/enum.Event.html#variant.EndFile
     pub fn observe_property<'a, T>(
         &'a mut self,
-        property: Property,
+        property: impl Into<PropertyName>,
     ) -> Result<impl Iterator<Item = Result<T>> + 'a>
     where
         T: TryFromValue,
     {
-        self.send_recv_command(Command::ObserveProperty(1, property))?;
+        let observation = self.observe(property)?;
 
-        let iter = EventIter::new(self, 1)
+        let iter = EventIter::new(self, vec![observation], true)
             .filter_map(Self::filter_property_change_event)
             .map(|property_change_event| match property_change_event {
                 Ok(event) => T::try_from(event.data),
@@ -245,34 +297,272 @@ impl MpvSocket {
         Ok(iter)
     }
 
+    /// Observe the given property until the returned handle is dropped.
+    ///
+    /// Unlike [`observe_property`], this does not borrow the socket,
+    /// changes of the property are delivered as [`Event::PropertyChange`] by [`events`].
+    /// Each observation gets a unique id,
+    /// so any number of observations can be active at the same time.
+    ///
+    /// Negative ids are reserved for these observations,
+    /// so observation ids passed to a raw `observe_property` [`command`] must not be negative.
+    ///
+    /// [`observe_property`]: #method.observe_property
+    /// [`events`]: #method.events
+    /// [`command`]: #method.command
+    /// [`Event::PropertyChange`]: ./event/enum.Event.html#variant.PropertyChange
+    pub fn observe(&mut self, property: impl Into<PropertyName>) -> Result<ObservationHandle> {
+        let property = property.into();
+        // Negative ids are reserved for observations, see `observe`.
+        let id = self.last_observe_id.advance(-1);
+        self.send_recv_command(Command::ObserveProperty(id, property.clone()))?;
+        self.observations.insert(id, property.clone());
+        Ok(ObservationHandle::new(
+            id,
+            property,
+            Arc::clone(&self.dropped_observations),
+        ))
+    }
+
+    /// Iterate over the active observations by id.
+    ///
+    /// This includes the observations of [`observe`] as well as [`observe_property`].
+    ///
+    /// [`observe`]: #method.observe
+    /// [`observe_property`]: #method.observe_property
+    pub fn observations(&self) -> impl Iterator<Item = (i64, &PropertyName)> + '_ {
+        self.observations
+            .iter()
+            .map(|(id, property)| (*id, property))
+    }
+
     /// Watch properties for changes.
     ///
     /// If one of the given properties is changed,
     /// then the iterator will return the next [`Property`].
     ///
-    /// When the returned iterator returns `None`,
-    /// the player, socket or file is closed and thus the `MpvSocket` should also be dropped.
-    /// All further calls may produce an error.
+    /// The returned iterator ends at the next [`Event::EndFile`],
+    /// that is when the current file ends,
+    /// and the socket stays usable for further calls.
+    /// It also ends when the player or socket is closed,
+    /// in which case the `MpvSocket` should also be dropped.
+    /// Other events received in the meantime, including the end-file event,
+    /// are kept and delivered by the next event iteration.
     ///
     /// See [`Properties`] for more information about properties.
     ///
     /// [`Properties`]: https://mpv.io/manual/master/#properties
     /// [`Property`]: ./enum.Property.html
+    /// [`Event::EndFile`]: ./event/enum.Event.html#variant.EndFile
     pub fn observe_properties<'a>(
         &'a mut self,
-        properties: impl IntoIterator<Item = Property>,
+        properties: impl IntoIterator<Item = impl Into<PropertyName>>,
     ) -> Result<impl Iterator<Item = Result<PropertyChangeEvent>> + 'a> {
-        let mut property_index = 0;
-        for property in properties {
-            property_index += 1;
-            self.send_recv_command(Command::ObserveProperty(property_index, property))?;
-        }
+        let observations = properties
+            .into_iter()
+            .map(|property| self.observe(property))
+            .collect::<Result<Vec<_>>>()?;
 
-        let iter = EventIter::new(self, property_index) //
+        let iter = EventIter::new(self, observations, true) //
             .filter_map(Self::filter_property_change_event);
         Ok(iter)
     }
 
+    /// Iterate over all events sent by mpv.
+    ///
+    /// This includes file transitions like [`Event::StartFile`] and [`Event::EndFile`],
+    /// seeks and changes of observed properties.
+    /// Unlike the iterators returned by [`observe_property`] and [`observe_properties`],
+    /// this iterator does not end when the current file ends.
+    ///
+    /// When the returned iterator returns `None`,
+    /// the player or socket is closed and thus the `MpvSocket` should also be dropped.
+    /// All further calls may produce an error.
+    ///
+    /// See [`List of events`] for more information about events.
+    ///
+    /// [`Event::StartFile`]: ./event/enum.Event.html#variant.StartFile
+    /// [`Event::EndFile`]: ./event/enum.Event.html#variant.EndFile
+    /// [`observe_property`]: #method.observe_property
+    /// [`observe_properties`]: #method.observe_properties
+    /// [`List of events`]: https://mpv.io/manual/master/#list-of-events
+    pub fn events(&mut self) -> impl Iterator<Item = Result<Event>> + '_ {
+        EventIter::new(self, Vec::new(), false).map(|event_response| match event_response {
+            Ok(event_response) => Ok(event_response.event),
+            Err(error) => Err(error),
+        })
+    }
+
+    /// Enable the given event, or all events for [`EventKind::All`].
+    ///
+    /// All events are enabled by default.
+    ///
+    /// [`EventKind::All`]: ./event/enum.EventKind.html#variant.All
+    pub fn enable_event(&mut self, event: EventKind) -> Result<()> {
+        self.send_recv_command(Command::EnableEvent(event))?;
+        Ok(())
+    }
+
+    /// Disable the given event, or all events for [`EventKind::All`].
+    ///
+    /// Disabling noisy events reduces the amount of data sent over the socket.
+    ///
+    /// [`EventKind::All`]: ./event/enum.EventKind.html#variant.All
+    pub fn disable_event(&mut self, event: EventKind) -> Result<()> {
+        self.send_recv_command(Command::DisableEvent(event))?;
+        Ok(())
+    }
+
+    /// Enable output of mpv log messages as [`Event::LogMessage`] events.
+    ///
+    /// Only messages with the given level or a more severe level are sent.
+    /// [`MpvLogLevel::No`] disables log messages again.
+    ///
+    /// See [`LogMessageEvent::forward_to_log`] to re-emit them through the `log` crate.
+    ///
+    /// [`Event::LogMessage`]: ./event/enum.Event.html#variant.LogMessage
+    /// [`MpvLogLevel::No`]: ./event/enum.MpvLogLevel.html#variant.No
+    /// [`LogMessageEvent::forward_to_log`]: ./event/struct.LogMessageEvent.html#method.forward_to_log
+    pub fn request_log_messages(&mut self, level: MpvLogLevel) -> Result<()> {
+        self.send_recv_command(Command::RequestLogMessages(level))?;
+        Ok(())
+    }
+
+    /// Register a handler for the given hook.
+    ///
+    /// The handler is run when mpv reaches the hook
+    /// and the hook is acknowledged automatically after the handler returned,
+    /// so mpv continues even if the handler fails.
+    /// While the handler runs, mpv waits,
+    /// so the handler can change properties like [`Property::StreamOpenFilename`]
+    /// to influence what the player does next.
+    ///
+    /// Handlers are only run while events are being iterated,
+    /// for example with [`events`] or [`observe_property`].
+    ///
+    /// Hooks with lower `priority` are run before hooks with higher `priority`.
+    /// Scripts use a priority of 50 by default.
+    ///
+    /// [`Property::StreamOpenFilename`]: ./enum.Property.html#variant.StreamOpenFilename
+    /// [`events`]: #method.events
+    /// [`observe_property`]: #method.observe_property
+    pub fn add_hook<F>(&mut self, hook: Hook, priority: i64, handler: F) -> Result<()>
+    where
+        F: FnMut(&mut MpvSocket) -> Result<()> + 'static,
+    {
+        // Negative ids are reserved for hooks with handlers, see `hook_add`.
+        let id = self.last_hook_id.advance(-1);
+        self.send_recv_command(Command::HookAdd(hook, id, priority))?;
+        self.hooks.insert(id, Box::new(handler));
+        Ok(())
+    }
+
+    /// Register the given hook with the given `id` without a handler.
+    ///
+    /// The resulting [`Event::Hook`] events are reported with the given `id`
+    /// in [`HookEvent::id`] and must be acknowledged with [`hook_ack`].
+    /// Prefer [`add_hook`], which takes care of this.
+    ///
+    /// Negative ids are reserved for hooks registered with [`add_hook`],
+    /// so the `id` must not be negative.
+    ///
+    /// [`Event::Hook`]: ./event/enum.Event.html#variant.Hook
+    /// [`HookEvent::id`]: ./event/struct.HookEvent.html#structfield.id
+    /// [`hook_ack`]: #method.hook_ack
+    /// [`add_hook`]: #method.add_hook
+    pub fn hook_add(&mut self, hook: Hook, id: i64, priority: i64) -> Result<()> {
+        if id < 0 {
+            return Err(Error::other(format!(
+                "hook id {} is reserved for add_hook, use an id of 0 or above",
+                id
+            )));
+        }
+        self.send_recv_command(Command::HookAdd(hook, id, priority))?;
+        Ok(())
+    }
+
+    /// Acknowledge a hook, so mpv continues.
+    ///
+    /// The `hook_id` is the one of the [`HookEvent`].
+    ///
+    /// [`HookEvent`]: ./event/struct.HookEvent.html
+    pub fn hook_ack(&mut self, hook_id: u64) -> Result<()> {
+        self.send_recv_command(Command::HookAck(hook_id))?;
+        Ok(())
+    }
+
+    fn run_hook(&mut self, id: i64, hook_id: u64) -> Result<()> {
+        let result = match self.hooks.remove(&id) {
+            Some(mut handler) => {
+                let result = handler(self);
+                self.hooks.insert(id, handler);
+                result
+            }
+            None => return Ok(()),
+        };
+        self.hook_ack(hook_id)?;
+        result
+    }
+
+    /// Send a message to all clients and scripts.
+    ///
+    /// By convention, the first argument is the name of the message,
+    /// which scripts can register handlers for with `mp.register_script_message`.
+    pub fn script_message(&mut self, args: &[&str]) -> Result<()> {
+        let args = args.iter().map(|arg| String::from(*arg)).collect();
+        self.send_recv_command(Command::ScriptMessage(args))?;
+        Ok(())
+    }
+
+    /// Send a message to the client or script with the given name.
+    ///
+    /// See [`script_message`] for more information.
+    ///
+    /// [`script_message`]: #method.script_message
+    pub fn script_message_to(&mut self, target: &str, args: &[&str]) -> Result<()> {
+        let args = args.iter().map(|arg| String::from(*arg)).collect();
+        self.send_recv_command(Command::ScriptMessageTo(target.to_owned(), args))?;
+        Ok(())
+    }
+
+    /// Register a handler for client messages with the given name.
+    ///
+    /// The handler is run for each [`Event::ClientMessage`]
+    /// whose first argument is the given name,
+    /// and receives the remaining arguments.
+    /// Registering another handler for the same name replaces the previous handler.
+    ///
+    /// Handlers are only run while events are being iterated,
+    /// for example with [`events`] or [`observe_property`].
+    ///
+    /// [`Event::ClientMessage`]: ./event/enum.Event.html#variant.ClientMessage
+    /// [`events`]: #method.events
+    /// [`observe_property`]: #method.observe_property
+    pub fn on_client_message<F>(&mut self, name: &str, handler: F)
+    where
+        F: FnMut(&mut MpvSocket, &[String]) -> Result<()> + 'static,
+    {
+        self.client_message_handlers
+            .insert(name.to_owned(), Box::new(handler));
+    }
+
+    fn run_client_message_handler(&mut self, event: &ClientMessageEvent) -> Result<()> {
+        let (name, args) = match event.args.split_first() {
+            Some(split) => split,
+            None => return Ok(()),
+        };
+        match self.client_message_handlers.remove(name) {
+            Some(mut handler) => {
+                let result = handler(self, args);
+                self.client_message_handlers
+                    .entry(name.clone())
+                    .or_insert(handler);
+                result
+            }
+            None => Ok(()),
+        }
+    }
+
     fn filter_property_change_event(
         event_response: Result<EventResponse>,
     ) -> Option<Result<PropertyChangeEvent>> {
@@ -304,149 +594,788 @@ impl MpvSocket {
     pub fn get_version(&mut self) -> Result<i64> {
         self.send_recv_convert_command(Command::GetVersion)
     }
-}
 
-impl MpvSocket {
-    fn send_recv_convert_command<T>(&mut self, command: Command) -> Result<T>
+    /// Run the given input command with the given arguments and return its result.
+    ///
+    /// This can be used to send any command mpv understands,
+    /// like `loadfile`, `seek` or `quit`.
+    ///
+    /// See [`List of Input Commands`] for more information about commands.
+    ///
+    /// [`List of Input Commands`]: https://mpv.io/manual/master/#list-of-input-commands
+    pub fn command<T>(&mut self, name: &str, args: impl IntoIterator<Item = Value>) -> Result<T>
     where
         T: TryFromValue,
     {
-        T::try_from(self.send_recv_command(command)?)
+        let args = args.into_iter().collect();
+        self.send_recv_convert_command(Command::Raw(name.to_owned(), args))
     }
 
-    fn send_recv_command(&mut self, command: Command) -> Result<Value> {
-        if self.closed {
-            return Err("mpv socket is closed".into());
-        }
+    /// Start a batch of commands which are sent at once.
+    ///
+    /// See [`Batch`] for more information.
+    ///
+    /// [`Batch`]: ./struct.Batch.html
+    pub fn batch(&mut self) -> Batch<'_> {
+        Batch::new(self)
+    }
 
-        let request = Request {
-            command,
-            request_id: self.last_request_id.next(),
-        };
-        let req_json = serde_json::to_vec(&request)?;
-        if log::log_enabled!(log::Level::Trace) {
-            log::trace!("sending: {}", String::from_utf8_lossy(&req_json));
-        }
+    /// Run the given input command with named arguments and return its result.
+    ///
+    /// See [`NamedCommand`] for more information.
+    ///
+    /// [`NamedCommand`]: ./struct.NamedCommand.html
+    pub fn command_named<T>(&mut self, command: NamedCommand) -> Result<T>
+    where
+        T: TryFromValue,
+    {
+        self.send_recv_convert_command(Command::Named(command))
+    }
 
-        let writer = self.socket.get_mut();
-        writer.write_all(&req_json)?;
-        writer.write_all(b"\n")?;
-        writer.flush()?;
+    /// Run the given input command asynchronously.
+    ///
+    /// Unlike [`command`], this returns as soon as the command was sent,
+    /// so long running commands like `loadfile` of a network stream or `screenshot-to-file`
+    /// don't block the connection.
+    /// Use the returned [`AsyncCommand`] to wait for the result or to abort the command.
+    ///
+    /// [`command`]: #method.command
+    /// [`AsyncCommand`]: ./struct.AsyncCommand.html
+    pub fn command_async(
+        &mut self,
+        name: &str,
+        args: impl IntoIterator<Item = Value>,
+    ) -> Result<AsyncCommand> {
+        let args = args.into_iter().collect();
+        self.send_async_command(Command::Raw(name.to_owned(), args))
+    }
 
-        loop {
-            self.read_buf.clear();
-            let num_bytes = self.socket.read_until(b'\n', &mut self.read_buf)?;
-            let res_json = String::from_utf8_lossy(&self.read_buf[..num_bytes]);
-            if log::log_enabled!(log::Level::Trace) {
-                log::trace!("received: {}", res_json.trim());
-            }
+    /// Load the given file or URL and play it or add it to the playlist, depending on `mode`.
+    ///
+    /// The given `options` are set as per-file options while the file is played,
+    /// like `&[("start", "30"), ("vid", "2")]`.
+    /// Passing options or [`LoadFileMode::InsertAt`] requires mpv 0.38 or newer.
+    ///
+    /// [`LoadFileMode::InsertAt`]: ./enum.LoadFileMode.html#variant.InsertAt
+    pub fn loadfile(
+        &mut self,
+        url: &str,
+        mode: LoadFileMode,
+        options: &[(&str, &str)],
+    ) -> Result<()> {
+        let options = options
+            .iter()
+            .map(|(key, value)| (String::from(*key), String::from(*value)))
+            .collect();
+        self.send_recv_command(Command::LoadFile(url.to_owned(), mode, options))?;
+        Ok(())
+    }
 
-            let response: CommandResponse = serde_json::from_str(res_json.as_ref())?;
+    /// Load the given playlist file or URL, depending on `mode`.
+    ///
+    /// Passing [`LoadFileMode::InsertAt`] requires mpv 0.38 or newer.
+    ///
+    /// [`LoadFileMode::InsertAt`]: ./enum.LoadFileMode.html#variant.InsertAt
+    pub fn loadlist(&mut self, url: &str, mode: LoadFileMode) -> Result<()> {
+        self.send_recv_command(Command::LoadList(url.to_owned(), mode))?;
+        Ok(())
+    }
 
-            if response.request_id == Some(request.request_id) {
-                return match response.error.as_ref().map(|error| error.as_str()) {
-                    Some("success") => Ok(response.data),
-                    Some(error) => Err(format!("mpv error response: {}", error).into()),
-                    None => Err(format!("unknown mpv response: {:?}", response).into()),
-                };
-            }
-        }
+    /// Change the playback position.
+    ///
+    /// The `mode` determines whether `target` is a time in seconds or a percentage,
+    /// and whether it is relative to the current position or absolute, see [`SeekMode`].
+    /// The precision of the seek is determined by the `--hr-seek` option,
+    /// use [`seek_with_precision`] to override it with a [`SeekPrecision`].
+    ///
+    /// [`SeekMode`]: ./enum.SeekMode.html
+    /// [`SeekPrecision`]: ./enum.SeekPrecision.html
+    /// [`seek_with_precision`]: #method.seek_with_precision
+    pub fn seek(&mut self, target: f64, mode: SeekMode) -> Result<()> {
+        self.seek_with_precision(target, mode, SeekPrecision::Default)
     }
-}
 
-struct EventIter<'a> {
-    mpv: &'a mut MpvSocket,
-    num_observed_properties: i64,
-}
+    /// Change the playback position with the given precision.
+    ///
+    /// See [`seek`] for more information.
+    ///
+    /// [`seek`]: #method.seek
+    pub fn seek_with_precision(
+        &mut self,
+        target: f64,
+        mode: SeekMode,
+        precision: SeekPrecision,
+    ) -> Result<()> {
+        self.send_recv_command(Command::Seek(target, mode, precision))?;
+        Ok(())
+    }
 
-impl<'a> EventIter<'a> {
-    fn new(mpv: &'a mut MpvSocket, num_observed_properties: i64) -> EventIter {
-        EventIter {
-            mpv,
-            num_observed_properties,
-        }
+    /// Undoes the seek command, and some other commands that seek (but not necessarily all of them).
+    ///
+    /// Calling this command once will jump to the playback position before the seek.
+    /// Calling it a second time undoes the revert-seek command itself.
+    pub fn revert_seek(&mut self) -> Result<()> {
+        self.send_recv_command(Command::RevertSeek)?;
+        Ok(())
     }
-}
 
-impl<'a> Iterator for EventIter<'a> {
-    type Item = Result<EventResponse>;
+    /// Play one frame, then pause.
+    ///
+    /// Does nothing with audio-only playback.
+    pub fn frame_step(&mut self) -> Result<()> {
+        self.send_recv_command(Command::FrameStep)?;
+        Ok(())
+    }
 
-    fn next(&mut self) -> Option<Result<EventResponse>> {
-        if self.mpv.closed {
-            return None;
-        }
+    /// Go back by one frame, then pause.
+    ///
+    /// Note that this can be very slow (it tries to be precise, not fast),
+    /// and sometimes fails to behave as expected.
+    pub fn frame_back_step(&mut self) -> Result<()> {
+        self.send_recv_command(Command::FrameBackStep)?;
+        Ok(())
+    }
 
-        let num_bytes = loop {
-            self.mpv.read_buf.clear();
+    /// Return the entries of the playlist.
+    pub fn playlist(&mut self) -> Result<Vec<PlaylistEntry>> {
+        self.get_property(Property::Playlist)
+    }
 
-            match self.mpv.socket.read_until(b'\n', &mut self.mpv.read_buf) {
-                Ok(num_bytes) => break num_bytes,
-                Err(io_error) => {
-                    if io_error.kind() == std::io::ErrorKind::WouldBlock {
-                        // Read timeout reached
-                        // can be ignored during event iteration
-                        log::trace!("ignoring WouldBlock error while advancing EventIter");
-                        continue;
-                    }
+    /// Go to the next entry on the playlist.
+    ///
+    /// If the last file on the playlist is currently played, nothing happens.
+    pub fn playlist_next(&mut self) -> Result<()> {
+        self.send_recv_command(Command::PlaylistNext)?;
+        Ok(())
+    }
 
-                    return Some(Err(io_error.into()));
-                }
-            }
-        };
+    /// Go to the previous entry on the playlist.
+    ///
+    /// If the first file on the playlist is currently played, nothing happens.
+    pub fn playlist_prev(&mut self) -> Result<()> {
+        self.send_recv_command(Command::PlaylistPrev)?;
+        Ok(())
+    }
 
-        if num_bytes == 0 {
-            return None;
-        }
+    /// Clear the playlist, except the currently played file.
+    pub fn playlist_clear(&mut self) -> Result<()> {
+        self.send_recv_command(Command::PlaylistClear)?;
+        Ok(())
+    }
 
-        let res_json = String::from_utf8_lossy(&self.mpv.read_buf[..num_bytes]);
-        if log::log_enabled!(log::Level::Trace) {
-            log::trace!("received: {}", res_json.trim());
-        }
+    /// Remove the playlist entry at the given index.
+    ///
+    /// Index values start counting with 0.
+    /// If the entry is currently playing, playback will be stopped.
+    pub fn playlist_remove(&mut self, index: i64) -> Result<()> {
+        self.send_recv_command(Command::PlaylistRemove(index))?;
+        Ok(())
+    }
 
-        let res_event: EventResponse = match serde_json::from_str(res_json.as_ref()) {
-            Ok(event) => event,
-            Err(error) => {
-                return Some(Err(error.into()));
-            }
-        };
+    /// Move the playlist entry at `index1`, so that it takes the place of the entry `index2`.
+    ///
+    /// (Paradoxically, the moved playlist entry will not have the index value `index2` after moving
+    /// if `index1` was lower than `index2`, because `index2` refers to the target entry,
+    /// not the index the entry will have after moving.)
+    pub fn playlist_move(&mut self, index1: i64, index2: i64) -> Result<()> {
+        self.send_recv_command(Command::PlaylistMove(index1, index2))?;
+        Ok(())
+    }
 
-        match &res_event.event {
-            Event::Shutdown | Event::EndFile(..) => self.mpv.closed = true,
-            _ => {}
-        }
+    /// Shuffle the playlist.
+    ///
+    /// This is similar to what is done on start if the `--shuffle` option is used.
+    pub fn playlist_shuffle(&mut self) -> Result<()> {
+        self.send_recv_command(Command::PlaylistShuffle)?;
+        Ok(())
+    }
 
-        let next = match res_event.error.as_ref().map(|error| error.as_str()) {
-            Some("success") | None => res_event,
-            Some(error) => return Some(Err(format!("mpv error response: {}", error).into())),
-        };
+    /// Attempt to revert the previous [`playlist_shuffle`] command.
+    ///
+    /// This works only once (multiple successive `playlist_unshuffle` commands do nothing).
+    /// May not work correctly if new recursive playlists have been opened since a `playlist_shuffle` command.
+    ///
+    /// [`playlist_shuffle`]: #method.playlist_shuffle
+    pub fn playlist_unshuffle(&mut self) -> Result<()> {
+        self.send_recv_command(Command::PlaylistUnshuffle)?;
+        Ok(())
+    }
 
-        return Some(Ok(next));
+    /// Return the audio, video and subtitle tracks of the current file.
+    pub fn tracks(&mut self) -> Result<Vec<Track>> {
+        self.get_property(Property::TrackList)
     }
-}
 
-impl<'a> Drop for EventIter<'a> {
-    fn drop(&mut self) {
-        if self.mpv.closed {
-            return;
-        }
+    /// Select the audio track.
+    pub fn set_audio_track(&mut self, track: TrackSelection) -> Result<()> {
+        self.set_property(Property::Aid, track)
+    }
 
-        let result = {
-            let mut result = Ok(());
-            for i in 1..=self.num_observed_properties {
-                match self.mpv.send_recv_command(Command::UnobserveProperty(i)) {
-                    Ok(_value) => {}
-                    Err(error) => {
-                        result = Err(error);
-                        break;
-                    }
+    /// Select the subtitle track.
+    pub fn set_sub_track(&mut self, track: TrackSelection) -> Result<()> {
+        self.set_property(Property::Sid, track)
+    }
+
+    /// Select the video track.
+    pub fn set_video_track(&mut self, track: TrackSelection) -> Result<()> {
+        self.set_property(Property::Vid, track)
+    }
+
+    /// Return the chapters of the current file.
+    pub fn chapters(&mut self) -> Result<Vec<Chapter>> {
+        self.get_property(Property::ChapterList)
+    }
+
+    /// Return the editions of the current file.
+    pub fn editions(&mut self) -> Result<Vec<Edition>> {
+        self.get_property(Property::EditionList)
+    }
+
+    /// Go to the next chapter.
+    ///
+    /// Going past the last chapter ends playback of the current file.
+    pub fn next_chapter(&mut self) -> Result<()> {
+        self.send_recv_command(Command::Add(Property::Chapter, Value::from(1)))?;
+        Ok(())
+    }
+
+    /// Go to the previous chapter.
+    ///
+    /// When not at the very start of the current chapter, this restarts the current chapter.
+    pub fn prev_chapter(&mut self) -> Result<()> {
+        self.send_recv_command(Command::Add(Property::Chapter, Value::from(-1)))?;
+        Ok(())
+    }
+
+    /// Go to the chapter with the given index.
+    ///
+    /// The number of the first chapter is 0.
+    pub fn seek_to_chapter(&mut self, index: i64) -> Result<()> {
+        self.set_property(Property::Chapter, index)
+    }
+}
+
+impl MpvSocket {
+    fn send_recv_convert_command<T>(&mut self, command: Command) -> Result<T>
+    where
+        T: TryFromValue,
+    {
+        T::try_from(self.send_recv_command(command)?)
+    }
+
+    pub(crate) fn send_recv_command(&mut self, command: Command) -> Result<Value> {
+        if self.closed {
+            return Err(Error::Closed);
+        }
+        self.unobserve_dropped_or_log();
+
+        let request = Request {
+            command,
+            request_id: self.last_request_id.next(),
+        };
+        let deadline = self.request_deadline();
+        self.send_request(&request)?;
+        self.recv_reply(request.request_id, deadline)
+    }
+
+    /// Unobserve dropped observations before the next request or event,
+    /// errors are only logged as they don't belong to the caller.
+    fn unobserve_dropped_or_log(&mut self) {
+        if let Err(error) = self.unobserve_dropped() {
+            log::error!("error while unobserving dropped observations: {}", error);
+        }
+    }
+
+    fn unobserve_dropped(&mut self) -> Result<()> {
+        let ids = std::mem::take(&mut *self.dropped_observations.lock().unwrap());
+        if ids.is_empty() {
+            return Ok(());
+        }
+
+        let mut result = Ok(());
+        for id in &ids {
+            if let Some(property) = self.observations.remove(id) {
+                log::trace!("unobserving {} ({})", property, id);
+            }
+            if result.is_ok() && !self.closed {
+                result = self
+                    .send_recv_command(Command::UnobserveProperty(*id))
+                    .map(|_value| ());
+            }
+        }
+
+        // Changes of properties which are no longer observed must not be delivered.
+        self.pending_events.retain(|pending| {
+            match (&pending.res_event.event, pending.res_event.id) {
+                (Event::PropertyChange(..), Some(id)) => !ids.contains(&id),
+                _ => true,
+            }
+        });
+        result
+    }
+
+    fn send_async_command(&mut self, command: Command) -> Result<AsyncCommand> {
+        if self.closed {
+            return Err(Error::Closed);
+        }
+        self.unobserve_dropped_or_log();
+
+        let request = AsyncRequest {
+            request: Request {
+                command,
+                request_id: self.last_request_id.next(),
+            },
+            is_async: true,
+        };
+        self.forget_dropped_async_commands();
+        self.send_request(&request)?;
+        let id = request.request.request_id;
+        self.async_commands.insert(id, None);
+        Ok(AsyncCommand::new(
+            id,
+            Arc::clone(&self.dropped_async_commands),
+        ))
+    }
+
+    /// Discard the replies of async commands whose handles were dropped without waiting.
+    fn forget_dropped_async_commands(&mut self) {
+        let ids = std::mem::take(&mut *self.dropped_async_commands.lock().unwrap());
+        for id in ids {
+            if self.async_commands.remove(&id).is_some() {
+                log::trace!("discarding reply of async command {}", id);
+            }
+        }
+    }
+
+    /// Wait for the reply of an async command,
+    /// until the given timeout or the deadline of a request sent now.
+    ///
+    /// The command is forgotten once its reply was received or the socket is closed,
+    /// after a timeout it can be waited on again.
+    pub(crate) fn wait_async_command(
+        &mut self,
+        id: i64,
+        timeout: Option<Duration>,
+    ) -> Result<Value> {
+        let deadline = match timeout {
+            Some(timeout) => Some(ReplyDeadline::strict(timeout)),
+            None => self.request_deadline(),
+        };
+        let result = match self.async_commands.get_mut(&id) {
+            Some(slot @ Some(_)) => reply_data(slot.take().unwrap()),
+            Some(None) if self.closed => Err(Error::Closed),
+            Some(None) => self.recv_reply(id, deadline),
+            None => Err(Error::other(format!("unknown async command: {}", id))),
+        };
+        if !matches!(result, Err(Error::Timeout)) {
+            self.async_commands.remove(&id);
+        }
+        result
+    }
+
+    fn send_request(&mut self, request: &impl serde::Serialize) -> Result<()> {
+        self.send_requests(std::slice::from_ref(request))
+    }
+
+    /// Write all requests at once, one per line.
+    fn send_requests<R: serde::Serialize>(&mut self, requests: &[R]) -> Result<()> {
+        let mut req_json = Vec::new();
+        for request in requests {
+            let start = req_json.len();
+            serde_json::to_writer(&mut req_json, request)?;
+            if log::log_enabled!(log::Level::Trace) {
+                log::trace!("sending: {}", String::from_utf8_lossy(&req_json[start..]));
+            }
+            req_json.push(b'\n');
+        }
+
+        let writer = self.socket.get_mut();
+        writer.write_all(&req_json)?;
+        writer.flush()?;
+        Ok(())
+    }
+
+    fn recv_reply(&mut self, request_id: i64, deadline: Option<ReplyDeadline>) -> Result<Value> {
+        let mut deadline = deadline;
+        loop {
+            let response = self.read_response(&mut deadline)?;
+            if response.request_id == Some(request_id) {
+                return reply_data(response);
+            }
+            self.keep_async_reply(response);
+        }
+    }
+
+    pub(crate) fn send_recv_batch(&mut self, commands: Vec<Command>) -> Result<Vec<Result<Value>>> {
+        if self.closed {
+            return Err(Error::Closed);
+        }
+        self.unobserve_dropped_or_log();
+
+        let requests = commands
+            .into_iter()
+            .map(|command| Request {
+                command,
+                request_id: self.last_request_id.next(),
+            })
+            .collect::<Vec<_>>();
+        let indices = requests
+            .iter()
+            .enumerate()
+            .map(|(index, request)| (request.request_id, index))
+            .collect::<HashMap<_, _>>();
+
+        let mut deadline = self.request_deadline();
+        self.send_requests(&requests)?;
+
+        let mut results: Vec<Option<Result<Value>>> = Vec::new();
+        results.resize_with(indices.len(), || None);
+        let mut remaining = indices.len();
+        while remaining > 0 {
+            let response = self.read_response(&mut deadline)?;
+            match response.request_id.and_then(|id| indices.get(&id)) {
+                Some(&index) if results[index].is_none() => {
+                    results[index] = Some(reply_data(response));
+                    remaining -= 1;
+                }
+                _ => self.keep_async_reply(response),
+            }
+        }
+        Ok(results.into_iter().map(Option::unwrap).collect())
+    }
+
+    /// Read the next command response.
+    ///
+    /// Events received in the meantime are kept
+    /// and delivered by the next event iteration.
+    fn read_response(&mut self, deadline: &mut Option<ReplyDeadline>) -> Result<CommandResponse> {
+        loop {
+            let line = match self.read_line(deadline.map(|deadline| deadline.at))? {
+                Some(line) => line,
+                None => {
+                    self.closed = true;
+                    return Err(Error::Closed);
+                }
+            };
+            if let Some(deadline) = deadline {
+                deadline.line_received();
+            }
+
+            let response: CommandResponse = serde_json::from_str(&line)?;
+            if response.request_id.is_none() {
+                if let Ok(res_event) = serde_json::from_str::<EventResponse>(&line) {
+                    let pending = self.new_pending_event(res_event);
+                    push_pending_event(&mut self.pending_events, pending);
+
+                    if let Some(deadline) = deadline.filter(|deadline| deadline.strict) {
+                        if Instant::now() >= deadline.at {
+                            return Err(Error::Timeout);
+                        }
+                    }
+                    continue;
+                }
+            }
+            return Ok(response);
+        }
+    }
+
+    /// Assign the next sequence number to a received event.
+    fn new_pending_event(&mut self, res_event: EventResponse) -> PendingEvent {
+        self.last_pending_event += 1;
+        PendingEvent {
+            seq: self.last_pending_event,
+            handled: false,
+            res_event,
+        }
+    }
+
+    /// Deadline for the reply of a request sent now.
+    fn request_deadline(&self) -> Option<ReplyDeadline> {
+        match (self.request_timeout, self.read_timeout) {
+            (Some(timeout), _) => Some(ReplyDeadline::strict(timeout)),
+            // Without a request timeout, only missing data is a timeout.
+            (None, Some(timeout)) => Some(ReplyDeadline {
+                at: Instant::now() + timeout,
+                timeout,
+                strict: false,
+            }),
+            (None, None) => None,
+        }
+    }
+
+    /// Read the next line, or `None` at the end of the stream.
+    ///
+    /// Reads which time out are retried until the deadline is reached,
+    /// or forever without a deadline.
+    /// Partially received lines are kept between retries.
+    fn read_line(&mut self, deadline: Option<Instant>) -> Result<Option<String>> {
+        loop {
+            match self.socket.read_until(b'\n', &mut self.read_buf) {
+                Ok(0) => {
+                    self.read_buf.clear();
+                    return Ok(None);
+                }
+                Ok(_) => {
+                    let line = String::from_utf8_lossy(&self.read_buf).into_owned();
+                    self.read_buf.clear();
+                    log::trace!("received: {}", line.trim());
+                    return Ok(Some(line));
+                }
+                Err(io_error)
+                    if io_error.kind() == std::io::ErrorKind::WouldBlock
+                        || io_error.kind() == std::io::ErrorKind::TimedOut =>
+                {
+                    match deadline {
+                        Some(deadline) if Instant::now() >= deadline => return Err(Error::Timeout),
+                        _ => log::trace!("read timeout reached, retrying"),
+                    }
                 }
+                Err(io_error) => return Err(io_error.into()),
             }
-            result
+        }
+    }
+
+    fn keep_async_reply(&mut self, response: CommandResponse) {
+        self.forget_dropped_async_commands();
+        if let Some(slot) = response
+            .request_id
+            .and_then(|id| self.async_commands.get_mut(&id))
+        {
+            *slot = Some(response);
+        }
+    }
+}
+
+/// Point in time after which waiting for a reply fails with [`Error::Timeout`].
+#[derive(Copy, Clone)]
+struct ReplyDeadline {
+    at: Instant,
+    timeout: Duration,
+    /// Whether the deadline also passes while mpv keeps sending events,
+    /// otherwise it is pushed forward whenever a line is received,
+    /// so it only passes while no data arrives.
+    strict: bool,
+}
+
+impl ReplyDeadline {
+    fn strict(timeout: Duration) -> ReplyDeadline {
+        ReplyDeadline {
+            at: Instant::now() + timeout,
+            timeout,
+            strict: true,
+        }
+    }
+
+    fn line_received(&mut self) {
+        if !self.strict {
+            self.at = Instant::now() + self.timeout;
+        }
+    }
+}
+
+/// Event which was received, but not yet delivered by an event iterator.
+struct PendingEvent {
+    /// Sequence number in the order the events were received.
+    seq: u64,
+    /// Whether hook and client message handlers already ran for the event.
+    handled: bool,
+    res_event: EventResponse,
+}
+
+/// Append an event to the pending events, dropping the oldest ones beyond the limit.
+fn push_pending_event(pending_events: &mut VecDeque<PendingEvent>, pending: PendingEvent) {
+    if pending_events.len() >= MAX_PENDING_EVENTS {
+        if let Some(dropped) = pending_events.pop_front() {
+            log::warn!("too many pending events, dropping: {:?}", dropped.res_event);
+        }
+    }
+    pending_events.push_back(pending);
+}
+
+fn reply_data(response: CommandResponse) -> Result<Value> {
+    match response.error.as_deref() {
+        Some("success") => Ok(response.data),
+        Some(error) => Err(Error::Mpv(MpvErrorCode::from_str(error))),
+        None => Err(Error::UnexpectedResponse(format!("{:?}", response))),
+    }
+}
+
+struct EventIter<'a> {
+    mpv: &'a mut MpvSocket,
+    observations: Vec<ObservationHandle>,
+    end_on_end_file: bool,
+    /// Pending events up to this sequence number were received before the iterator was created.
+    created_after: u64,
+    /// Events of other observations or other kinds than property changes,
+    /// which are put back into the pending events when the iterator is dropped.
+    skipped: VecDeque<PendingEvent>,
+    ended: bool,
+}
+
+impl<'a> EventIter<'a> {
+    fn new(
+        mpv: &'a mut MpvSocket,
+        observations: Vec<ObservationHandle>,
+        end_on_end_file: bool,
+    ) -> EventIter<'a> {
+        let created_after = mpv.last_pending_event;
+        EventIter {
+            mpv,
+            observations,
+            end_on_end_file,
+            created_after,
+            skipped: VecDeque::new(),
+            ended: false,
+        }
+    }
+}
+
+impl<'a> EventIter<'a> {
+    /// Whether the event is not a property change of the observations of this iterator,
+    /// and should be kept for the next event iteration.
+    ///
+    /// Iterators without observations deliver all events.
+    fn is_other_event(&self, res_event: &EventResponse) -> bool {
+        if self.observations.is_empty() {
+            return false;
+        }
+        match (&res_event.event, res_event.id) {
+            (Event::PropertyChange(..), Some(id)) => self
+                .observations
+                .iter()
+                .all(|observation| observation.id() != id),
+            _ => true,
+        }
+    }
+
+    /// Whether the event is a property change of an observation which was dropped.
+    ///
+    /// Changes of properties observed with a raw `observe_property` command
+    /// have ids of 0 or above and are kept.
+    fn is_unobserved_property_change(&self, res_event: &EventResponse) -> bool {
+        match (&res_event.event, res_event.id) {
+            (Event::PropertyChange(..), Some(id)) => {
+                id < 0 && !self.mpv.observations.contains_key(&id)
+            }
+            _ => false,
+        }
+    }
+
+    fn next_event(&mut self) -> Option<Result<PendingEvent>> {
+        if self.mpv.closed || self.ended {
+            return None;
+        }
+        self.mpv.unobserve_dropped_or_log();
+
+        // Without an event timeout, an idle player is waited on forever.
+        let deadline = self
+            .mpv
+            .event_timeout
+            .map(|timeout| Instant::now() + timeout);
+        let mut pending = match self.mpv.pending_events.pop_front() {
+            Some(pending) => pending,
+            None => loop {
+                let res_json = match self.mpv.read_line(deadline) {
+                    Ok(Some(line)) => line,
+                    Ok(None) => return None,
+                    Err(error) => return Some(Err(error)),
+                };
+
+                // Replies of async commands can arrive at any time,
+                // keep them until they are waited on.
+                if let Ok(response) = serde_json::from_str::<CommandResponse>(res_json.as_ref()) {
+                    if response.request_id.is_some() {
+                        self.mpv.keep_async_reply(response);
+                        continue;
+                    }
+                }
+
+                match serde_json::from_str(res_json.as_ref()) {
+                    Ok(res_event) => break self.mpv.new_pending_event(res_event),
+                    Err(error) => {
+                        return Some(Err(error.into()));
+                    }
+                }
+            },
         };
+
+        let stale = pending.seq <= self.created_after;
+        match &pending.res_event.event {
+            Event::Shutdown => self.mpv.closed = true,
+            // An end-file received before the iterator was created belongs to a previous file,
+            // like the one replaced by `loadfile` right before observing a property.
+            Event::EndFile(..) if self.end_on_end_file && !stale => self.ended = true,
+            _ => {}
+        }
+
+        // Handlers run once, even if the event is skipped and delivered again later.
+        if !pending.handled {
+            pending.handled = true;
+            let res_event = &pending.res_event;
+            let handler_result = match (&res_event.event, res_event.id) {
+                (Event::Hook(hook_event), Some(id)) => self.mpv.run_hook(id, hook_event.hook_id),
+                (Event::ClientMessage(client_message_event), _) => {
+                    self.mpv.run_client_message_handler(client_message_event)
+                }
+                _ => Ok(()),
+            };
+            if let Err(error) = handler_result {
+                return Some(Err(error));
+            }
+        }
+
+        match pending.res_event.error.as_deref() {
+            Some("success") | None => Some(Ok(pending)),
+            Some(error) => Some(Err(Error::Mpv(MpvErrorCode::from_str(error)))),
+        }
+    }
+}
+
+impl<'a> Iterator for EventIter<'a> {
+    type Item = Result<EventResponse>;
+
+    fn next(&mut self) -> Option<Result<EventResponse>> {
+        loop {
+            let pending = match self.next_event()? {
+                Ok(pending) => pending,
+                Err(error) => return Some(Err(error)),
+            };
+            if self.is_unobserved_property_change(&pending.res_event) {
+                log::debug!(
+                    "filtered event of dropped observation: {:?}",
+                    pending.res_event
+                );
+            } else if self.is_other_event(&pending.res_event) {
+                log::trace!("keeping event for later: {:?}", pending.res_event);
+                push_pending_event(&mut self.skipped, pending);
+            } else {
+                return Some(Ok(pending.res_event));
+            }
+        }
+    }
+}
+
+impl<'a> Drop for EventIter<'a> {
+    fn drop(&mut self) {
+        // Skipped events were received before the remaining pending events.
+        let pending_events = std::mem::take(&mut self.mpv.pending_events);
+        for pending in std::mem::take(&mut self.skipped)
+            .into_iter()
+            .chain(pending_events)
+        {
+            push_pending_event(&mut self.mpv.pending_events, pending);
+        }
+
+        self.observations.clear();
+        if self.mpv.closed {
+            return;
+        }
+
+        let result = self.mpv.unobserve_dropped();
         match result {
             Ok(json) => json,
             Err(error) => {
-                if let Some(io_error) = error.downcast_ref::<std::io::Error>() {
+                if let Error::Io(io_error) = &error {
                     #[cfg(target_os = "windows")]
                     if io_error.raw_os_error() == Some(ERROR_NO_DATA) {
                         // Ignore this error,
@@ -482,7 +1411,7 @@ mod tests {
         MpvSocket::connect(r"\\.\pipe\mpv-socket").unwrap()
     }
 
-    #[cfg(target_os = "linux")]
+    #[cfg(unix)]
     fn init() -> MpvSocket {
         let _ = pretty_env_logger::try_init_timed();
         MpvSocket::connect("/tmp/mpv-socket").unwrap()
@@ -597,4 +1526,1017 @@ mod tests {
         log::info!("Version: {}", version);
         assert_ne!(version, 0);
     }
+
+    #[test]
+    fn command_get_property() {
+        let mut mpv_socket = init();
+        let volume: f64 = mpv_socket
+            .command("get_property", vec![Value::from("volume")])
+            .unwrap();
+        log::info!("Volume: {:?}", volume);
+        assert_ne!(volume, -1.0);
+    }
+
+    #[test]
+    fn seek_relative() {
+        let mut mpv_socket = init();
+        mpv_socket.seek(-1.0, SeekMode::Relative).unwrap();
+        log::info!("Seeked back by one second");
+    }
+
+    #[test]
+    fn playlist() {
+        let mut mpv_socket = init();
+        let playlist = mpv_socket.playlist().unwrap();
+        log::info!("Playlist: {:?}", playlist);
+        assert!(!playlist.is_empty());
+    }
+
+    #[test]
+    fn tracks() {
+        let mut mpv_socket = init();
+        let tracks = mpv_socket.tracks().unwrap();
+        log::info!("Tracks: {:?}", tracks);
+        assert!(!tracks.is_empty());
+    }
+
+    #[test]
+    fn chapters() {
+        let mut mpv_socket = init();
+        let chapters = mpv_socket.chapters().unwrap();
+        log::info!("Chapters: {:?}", chapters);
+    }
+
+    #[test]
+    fn get_property_metadata_by_key() {
+        let mut mpv_socket = init();
+        let title: Value = mpv_socket
+            .get_property(PropertyName::metadata_by_key("title"))
+            .unwrap();
+        log::info!("Title: {:?}", title);
+    }
+
+    #[test]
+    fn events_after_seek() {
+        let mut mpv_socket = init();
+        mpv_socket.seek(-1.0, SeekMode::Relative).unwrap();
+
+        for result in mpv_socket.events().take(25) {
+            let event = result.unwrap();
+            log::info!("Event: {:?}", event);
+            if event == Event::PlaybackRestart {
+                return;
+            }
+        }
+        panic!("no playback-restart event received after seek");
+    }
+
+    #[test]
+    fn disable_event_tick() {
+        let mut mpv_socket = init();
+        mpv_socket.disable_event(EventKind::Tick).unwrap();
+        log::info!("Disabled tick event");
+    }
+
+    #[test]
+    fn request_log_messages() {
+        let mut mpv_socket = init();
+        mpv_socket.request_log_messages(MpvLogLevel::V).unwrap();
+        mpv_socket.seek(-1.0, SeekMode::Relative).unwrap();
+
+        for result in mpv_socket.events().take(25) {
+            if let Event::LogMessage(log_message) = result.unwrap() {
+                log_message.forward_to_log();
+                return;
+            }
+        }
+        panic!("no log message received");
+    }
+
+    #[test]
+    fn add_hook_on_load() {
+        use std::cell::Cell;
+        use std::rc::Rc;
+
+        let mut mpv_socket = init();
+        let called = Rc::new(Cell::new(false));
+        let handler_called = Rc::clone(&called);
+        mpv_socket
+            .add_hook(Hook::OnLoad, 50, move |mpv_socket| {
+                let path: String = mpv_socket.get_property(Property::StreamOpenFilename)?;
+                log::info!("Loading: {}", path);
+                handler_called.set(true);
+                Ok(())
+            })
+            .unwrap();
+
+        let path: String = mpv_socket.get_property(Property::Path).unwrap();
+        mpv_socket
+            .loadfile(&path, LoadFileMode::Replace, &[])
+            .unwrap();
+
+        for result in mpv_socket.events().take(25) {
+            if result.unwrap() == Event::FileLoaded {
+                break;
+            }
+        }
+        assert!(called.get());
+    }
+
+    #[test]
+    fn on_client_message() {
+        use std::cell::RefCell;
+        use std::rc::Rc;
+
+        let mut mpv_socket = init();
+        let received = Rc::new(RefCell::new(Vec::new()));
+        let handler_received = Rc::clone(&received);
+        mpv_socket.on_client_message("mpv-socket-test", move |_mpv_socket, args| {
+            handler_received.borrow_mut().extend_from_slice(args);
+            Ok(())
+        });
+
+        mpv_socket
+            .script_message(&["mpv-socket-test", "a", "b"])
+            .unwrap();
+
+        for result in mpv_socket.events().take(25) {
+            if let Event::ClientMessage(..) = result.unwrap() {
+                break;
+            }
+        }
+        assert_eq!(*received.borrow(), vec!["a", "b"]);
+    }
+
+    #[test]
+    fn command_async() {
+        let mut mpv_socket = init();
+        let expand_text = mpv_socket
+            .command_async("expand-text", vec![Value::from("${mpv-version}")])
+            .unwrap();
+        let version: i64 = mpv_socket.get_version().unwrap();
+        assert!(version > 0);
+        let text: String = expand_text.wait(&mut mpv_socket).unwrap();
+        assert!(text.starts_with("mpv "));
+    }
+
+    #[test]
+    fn command_named() {
+        let mut mpv_socket = init();
+        let text: String = mpv_socket
+            .command_named(NamedCommand::new("expand-text").arg("text", "${mpv-version}"))
+            .unwrap();
+        assert!(text.starts_with("mpv "));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn client_set_property_while_observing() {
+        let _ = pretty_env_logger::try_init_timed();
+        let client = MpvClient::connect("/tmp/mpv-socket").unwrap();
+        client.set_property(Property::Pause, true).unwrap();
+
+        let mut pause_changes = client.observe_property::<bool>(Property::Pause).unwrap();
+        assert!(pause_changes.next().unwrap().unwrap());
+
+        let setter = client.clone();
+        std::thread::spawn(move || setter.set_property(Property::Pause, false).unwrap())
+            .join()
+            .unwrap();
+        assert!(!pause_changes.next().unwrap().unwrap());
+    }
+
+    #[cfg(all(unix, feature = "tokio"))]
+    #[test]
+    fn async_client_set_property_while_observing() {
+        use futures_core::Stream;
+        use std::pin::Pin;
+
+        let _ = pretty_env_logger::try_init_timed();
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        runtime.block_on(async {
+            let client = async_client::MpvClient::connect("/tmp/mpv-socket")
+                .await
+                .unwrap();
+            client.set_property(Property::Pause, true).await.unwrap();
+
+            let mut pause_changes = client
+                .observe_property::<bool>(Property::Pause)
+                .await
+                .unwrap();
+            let first = std::future::poll_fn(|cx| Pin::new(&mut pause_changes).poll_next(cx));
+            assert!(first.await.unwrap().unwrap());
+
+            client.set_property(Property::Pause, false).await.unwrap();
+            let second = std::future::poll_fn(|cx| Pin::new(&mut pause_changes).poll_next(cx));
+            assert!(!second.await.unwrap().unwrap());
+        });
+    }
+
+    #[test]
+    fn batch() {
+        let mut mpv_socket = init();
+        let mut results = mpv_socket
+            .batch()
+            .set(Property::Volume, 50.0)
+            .get(Property::Volume)
+            .get(Property::Pause)
+            .send()
+            .unwrap();
+        assert_eq!(results.len(), 3);
+        assert_eq!(results.take::<Value>(0).unwrap(), Value::Null);
+        assert_eq!(results.take::<f64>(1).unwrap(), 50.0);
+        results.take::<bool>(2).unwrap();
+        assert!(results.take::<bool>(2).is_err());
+    }
+}
+
+// These tests replay recorded mpv output with an in-memory stream.
+#[cfg(test)]
+mod mock_tests {
+    use super::*;
+    use crate::event::HookEvent;
+    use crate::mock::{mock, mock_chunks, mock_stream};
+    use std::time::Duration;
+
+    #[test]
+    fn events_received_before_reply_are_kept() {
+        let (mut mpv_socket, output) = mock(&[
+            r#"{"event":"seek"}"#,
+            r#"{"event":"property-change","id":3,"name":"volume","data":50.0}"#,
+            r#"{"request_id":1,"error":"success"}"#,
+            r#"{"event":"playback-restart"}"#,
+        ]);
+
+        mpv_socket.set_property(Property::Volume, 50.0).unwrap();
+        assert_eq!(
+            String::from_utf8_lossy(&output.borrow()),
+            "{\"command\":[\"set_property\",\"volume\",50.0],\"request_id\":1}\n"
+        );
+
+        let events = mpv_socket.events().collect::<Result<Vec<_>>>().unwrap();
+        assert_eq!(
+            events,
+            vec![
+                Event::Seek,
+                Event::PropertyChange(PropertyChangeEvent {
+                    name: PropertyName::Known(Property::Volume),
+                    data: Value::from(50.0),
+                }),
+                Event::PlaybackRestart,
+            ]
+        );
+    }
+
+    #[test]
+    fn events_received_between_replies_are_kept_in_order() {
+        let (mut mpv_socket, _output) = mock(&[
+            r#"{"event":"start-file","playlist_entry_id":1}"#,
+            r#"{"request_id":1,"error":"success","data":"ipc-1"}"#,
+            r#"{"event":"file-loaded"}"#,
+            r#"{"request_id":2,"error":"success","data":true}"#,
+        ]);
+
+        assert_eq!(mpv_socket.client_name().unwrap(), "ipc-1");
+        assert!(mpv_socket.get_property::<bool>(Property::Pause).unwrap());
+
+        let mut events = mpv_socket.events();
+        assert!(matches!(events.next(), Some(Ok(Event::StartFile(..)))));
+        assert!(matches!(events.next(), Some(Ok(Event::FileLoaded))));
+        assert!(events.next().is_none());
+    }
+
+    #[test]
+    fn observed_property_change_received_before_reply_is_kept() {
+        let (mut mpv_socket, _output) = mock(&[
+            r#"{"event":"property-change","id":-1,"name":"volume","data":80.0}"#,
+            r#"{"request_id":1,"error":"success"}"#,
+            r#"{"event":"property-change","id":-1,"name":"volume","data":90.0}"#,
+            r#"{"request_id":2,"error":"success"}"#,
+        ]);
+
+        let volumes = mpv_socket
+            .observe_property::<f64>(Property::Volume)
+            .unwrap()
+            .take(2)
+            .collect::<Result<Vec<_>>>()
+            .unwrap();
+        assert_eq!(volumes, vec![80.0, 90.0]);
+    }
+
+    #[test]
+    fn observe_property_ignores_end_file_received_before_it() {
+        let (mut mpv_socket, _output) = mock(&[
+            r#"{"request_id":1,"error":"success"}"#,
+            r#"{"event":"end-file","reason":"stop"}"#,
+            r#"{"request_id":2,"error":"success"}"#,
+            r#"{"event":"property-change","id":-1,"name":"volume","data":70.0}"#,
+            r#"{"event":"end-file","reason":"eof"}"#,
+            r#"{"event":"property-change","id":-1,"name":"volume","data":80.0}"#,
+        ]);
+
+        mpv_socket
+            .loadfile("video.mkv", LoadFileMode::Replace, &[])
+            .unwrap();
+        let volumes = mpv_socket
+            .observe_property::<f64>(Property::Volume)
+            .unwrap()
+            .collect::<Result<Vec<_>>>()
+            .unwrap();
+        assert_eq!(volumes, vec![70.0]);
+    }
+
+    #[test]
+    fn property_changes_of_dropped_observation_are_discarded() {
+        let (mut mpv_socket, _output) = mock(&[
+            r#"{"request_id":1,"error":"success"}"#,
+            r#"{"event":"property-change","id":-1,"name":"volume","data":80.0}"#,
+            r#"{"event":"property-change","id":-1,"name":"volume","data":90.0}"#,
+            r#"{"request_id":2,"error":"success"}"#,
+            r#"{"event":"seek"}"#,
+        ]);
+
+        let volume = mpv_socket
+            .observe_property::<f64>(Property::Volume)
+            .unwrap()
+            .next()
+            .unwrap()
+            .unwrap();
+        assert_eq!(volume, 80.0);
+
+        let events = mpv_socket.events().collect::<Result<Vec<_>>>().unwrap();
+        assert_eq!(events, vec![Event::Seek]);
+    }
+
+    #[test]
+    fn events_unobserve_dropped_observation() {
+        let (mut mpv_socket, output) = mock(&[
+            r#"{"request_id":1,"error":"success"}"#,
+            r#"{"event":"property-change","id":-1,"name":"volume","data":80.0}"#,
+            r#"{"request_id":2,"error":"success"}"#,
+            r#"{"event":"property-change","id":-1,"name":"volume","data":90.0}"#,
+            r#"{"event":"seek"}"#,
+        ]);
+
+        drop(mpv_socket.observe(Property::Volume).unwrap());
+        let events = mpv_socket.events().collect::<Result<Vec<_>>>().unwrap();
+        assert_eq!(events, vec![Event::Seek]);
+        assert!(String::from_utf8_lossy(&output.borrow())
+            .ends_with("{\"command\":[\"unobserve_property\",-1],\"request_id\":2}\n"));
+    }
+
+    #[test]
+    fn events_keep_changes_of_raw_observe_property() {
+        let (mut mpv_socket, _output) = mock(&[
+            r#"{"request_id":1,"error":"success"}"#,
+            r#"{"request_id":2,"error":"success"}"#,
+            r#"{"event":"property-change","id":-1,"name":"volume","data":80.0}"#,
+            r#"{"request_id":3,"error":"success"}"#,
+            r#"{"event":"property-change","id":1,"name":"volume","data":90.0}"#,
+        ]);
+
+        drop(mpv_socket.observe(Property::Volume).unwrap());
+        mpv_socket
+            .command::<Value>("observe_property", [Value::from(1), Value::from("volume")])
+            .unwrap();
+        let events = mpv_socket.events().collect::<Result<Vec<_>>>().unwrap();
+        assert_eq!(
+            events,
+            vec![Event::PropertyChange(PropertyChangeEvent {
+                name: PropertyName::Known(Property::Volume),
+                data: Value::from(90.0),
+            })]
+        );
+    }
+
+    #[test]
+    fn observations_get_unique_ids() {
+        let (mut mpv_socket, output) = mock(&[
+            r#"{"request_id":1,"error":"success"}"#,
+            r#"{"request_id":2,"error":"success"}"#,
+            r#"{"request_id":3,"error":"success"}"#,
+            r#"{"request_id":4,"error":"success","data":"ipc-1"}"#,
+        ]);
+
+        let volume = mpv_socket.observe(Property::Volume).unwrap();
+        let pause = mpv_socket.observe(Property::Pause).unwrap();
+        assert_eq!(volume.id(), -1);
+        assert_eq!(pause.id(), -2);
+        assert_eq!(pause.property(), &PropertyName::Known(Property::Pause));
+
+        drop(volume);
+        assert_eq!(mpv_socket.client_name().unwrap(), "ipc-1");
+        assert_eq!(
+            mpv_socket.observations().collect::<Vec<_>>(),
+            vec![(-2, &PropertyName::Known(Property::Pause))]
+        );
+        assert_eq!(
+            String::from_utf8_lossy(&output.borrow()),
+            concat!(
+                "{\"command\":[\"observe_property\",-1,\"volume\"],\"request_id\":1}\n",
+                "{\"command\":[\"observe_property\",-2,\"pause\"],\"request_id\":2}\n",
+                "{\"command\":[\"unobserve_property\",-1],\"request_id\":3}\n",
+                "{\"command\":[\"client_name\"],\"request_id\":4}\n",
+            )
+        );
+    }
+
+    #[test]
+    fn observe_property_ignores_other_observations() {
+        let (mut mpv_socket, output) = mock(&[
+            r#"{"request_id":1,"error":"success"}"#,
+            r#"{"request_id":2,"error":"success"}"#,
+            r#"{"event":"property-change","id":-1,"name":"pause","data":true}"#,
+            r#"{"event":"property-change","id":-2,"name":"volume","data":70.0}"#,
+            r#"{"request_id":3,"error":"success"}"#,
+        ]);
+
+        let _pause = mpv_socket.observe(Property::Pause).unwrap();
+        let volume = mpv_socket
+            .observe_property::<f64>(Property::Volume)
+            .unwrap()
+            .next()
+            .unwrap()
+            .unwrap();
+        assert_eq!(volume, 70.0);
+
+        let output = String::from_utf8_lossy(&output.borrow()).into_owned();
+        assert!(output.ends_with("{\"command\":[\"unobserve_property\",-2],\"request_id\":3}\n"));
+        assert!(!output.contains("[\"unobserve_property\",-1]"));
+    }
+
+    #[test]
+    fn observe_property_keeps_events_of_other_observations() {
+        let (mut mpv_socket, _output) = mock(&[
+            r#"{"request_id":1,"error":"success"}"#,
+            r#"{"request_id":2,"error":"success"}"#,
+            r#"{"event":"property-change","id":-1,"name":"pause","data":true}"#,
+            r#"{"event":"seek"}"#,
+            r#"{"event":"property-change","id":-2,"name":"volume","data":70.0}"#,
+            r#"{"event":"end-file","reason":"eof"}"#,
+            r#"{"request_id":3,"error":"success"}"#,
+        ]);
+
+        let _pause = mpv_socket.observe(Property::Pause).unwrap();
+        let volumes = mpv_socket
+            .observe_property::<f64>(Property::Volume)
+            .unwrap()
+            .collect::<Result<Vec<_>>>()
+            .unwrap();
+        assert_eq!(volumes, vec![70.0]);
+
+        let mut events = mpv_socket.events();
+        assert_eq!(
+            events.next().unwrap().unwrap(),
+            Event::PropertyChange(PropertyChangeEvent {
+                name: PropertyName::Known(Property::Pause),
+                data: Value::from(true),
+            })
+        );
+        assert_eq!(events.next().unwrap().unwrap(), Event::Seek);
+        assert!(matches!(events.next(), Some(Ok(Event::EndFile(..)))));
+        assert!(events.next().is_none());
+    }
+
+    #[test]
+    fn manual_hook_ids_do_not_run_handlers() {
+        let (mut mpv_socket, output) = mock(&[
+            r#"{"request_id":1,"error":"success"}"#,
+            r#"{"request_id":2,"error":"success"}"#,
+            r#"{"event":"hook","id":1,"hook_id":7}"#,
+            r#"{"event":"hook","id":-1,"hook_id":8}"#,
+            r#"{"request_id":3,"error":"success"}"#,
+        ]);
+
+        mpv_socket.add_hook(Hook::OnLoad, 50, |_| Ok(())).unwrap();
+        mpv_socket.hook_add(Hook::OnLoad, 1, 50).unwrap();
+        assert!(mpv_socket.hook_add(Hook::OnLoad, -1, 50).is_err());
+
+        let events = mpv_socket.events().collect::<Result<Vec<_>>>().unwrap();
+        assert_eq!(
+            events,
+            vec![
+                Event::Hook(HookEvent { id: 1, hook_id: 7 }),
+                Event::Hook(HookEvent { id: -1, hook_id: 8 }),
+            ]
+        );
+        assert_eq!(
+            String::from_utf8_lossy(&output.borrow()),
+            concat!(
+                "{\"command\":[\"hook-add\",\"on_load\",-1,50],\"request_id\":1}\n",
+                "{\"command\":[\"hook-add\",\"on_load\",1,50],\"request_id\":2}\n",
+                "{\"command\":[\"hook-ack\",8],\"request_id\":3}\n",
+            )
+        );
+    }
+
+    #[test]
+    fn client_messages_run_handlers_by_name_once() {
+        use std::cell::RefCell;
+        use std::rc::Rc;
+
+        let (mut mpv_socket, _output) = mock(&[
+            r#"{"request_id":1,"error":"success"}"#,
+            r#"{"event":"client-message","args":["greet","a","b"]}"#,
+            r#"{"event":"client-message","args":["other","c"]}"#,
+            r#"{"event":"client-message","args":[]}"#,
+            r#"{"event":"property-change","id":-1,"name":"volume","data":70.0}"#,
+            r#"{"request_id":2,"error":"success"}"#,
+        ]);
+
+        let received = Rc::new(RefCell::new(Vec::new()));
+        let handler_received = Rc::clone(&received);
+        mpv_socket.on_client_message("greet", move |_mpv_socket, args| {
+            handler_received.borrow_mut().push(args.to_vec());
+            Ok(())
+        });
+
+        let volume = mpv_socket
+            .observe_property::<f64>(Property::Volume)
+            .unwrap()
+            .next()
+            .unwrap()
+            .unwrap();
+        assert_eq!(volume, 70.0);
+        assert_eq!(*received.borrow(), vec![vec!["a", "b"]]);
+
+        let events = mpv_socket.events().collect::<Result<Vec<_>>>().unwrap();
+        let client_message = |args: &[&str]| {
+            Event::ClientMessage(ClientMessageEvent {
+                args: args.iter().map(|arg| arg.to_string()).collect(),
+            })
+        };
+        assert_eq!(
+            events,
+            vec![
+                client_message(&["greet", "a", "b"]),
+                client_message(&["other", "c"]),
+                client_message(&[]),
+            ]
+        );
+        assert_eq!(*received.borrow(), vec![vec!["a", "b"]]);
+    }
+
+    #[test]
+    fn async_reply_received_while_reading_other_replies_and_events() {
+        let (mut mpv_socket, _output) = mock(&[
+            r#"{"request_id":1,"error":"success","data":"mpv 0.38.0"}"#,
+            r#"{"request_id":3,"error":"success","data":"ipc-1"}"#,
+            r#"{"event":"seek"}"#,
+            r#"{"request_id":2,"error":"success","data":"/tmp/shot.png"}"#,
+            r#"{"event":"playback-restart"}"#,
+        ]);
+
+        let version = mpv_socket
+            .command_async("expand-text", vec![Value::from("${mpv-version}")])
+            .unwrap();
+        let screenshot = mpv_socket
+            .command_async("screenshot-to-file", vec![Value::from("/tmp/shot.png")])
+            .unwrap();
+        assert_eq!(mpv_socket.client_name().unwrap(), "ipc-1");
+
+        let events = mpv_socket.events().collect::<Result<Vec<_>>>().unwrap();
+        assert_eq!(events, vec![Event::Seek, Event::PlaybackRestart]);
+
+        let version: String = version.wait(&mut mpv_socket).unwrap();
+        assert_eq!(version, "mpv 0.38.0");
+        let path: String = screenshot.wait(&mut mpv_socket).unwrap();
+        assert_eq!(path, "/tmp/shot.png");
+        assert!(mpv_socket.async_commands.is_empty());
+    }
+
+    #[test]
+    fn wait_async_command_timeout() {
+        let (mut mpv_socket, _output) = mock_chunks(&[Some("{\"event\":\"seek\"}\n")], true);
+        mpv_socket.read_timeout = Some(Duration::from_millis(10));
+
+        let screenshot = mpv_socket
+            .command_async("screenshot-to-file", vec![Value::from("/tmp/shot.png")])
+            .unwrap();
+        assert!(matches!(
+            screenshot.wait::<Value>(&mut mpv_socket),
+            Err(Error::Timeout)
+        ));
+
+        let mut screenshot = mpv_socket
+            .command_async("screenshot-to-file", vec![Value::from("/tmp/shot.png")])
+            .unwrap();
+        assert!(matches!(
+            screenshot.wait_timeout::<Value>(&mut mpv_socket, Duration::from_millis(10)),
+            Err(Error::Timeout)
+        ));
+
+        // The reply arrives late, while reading for another request.
+        let reply = r#"{"request_id":2,"error":"success","data":"/tmp/shot.png"}"#;
+        mpv_socket.keep_async_reply(serde_json::from_str(reply).unwrap());
+        let path: String = screenshot
+            .wait_timeout(&mut mpv_socket, Duration::from_millis(10))
+            .unwrap();
+        assert_eq!(path, "/tmp/shot.png");
+        assert!(mpv_socket.async_commands.is_empty());
+    }
+
+    #[test]
+    fn reply_of_dropped_async_command_is_discarded() {
+        let (mut mpv_socket, _output) = mock(&[
+            r#"{"request_id":1,"error":"success"}"#,
+            r#"{"request_id":2,"error":"success","data":"ipc-1"}"#,
+        ]);
+
+        drop(
+            mpv_socket
+                .command_async("screenshot-to-file", vec![Value::from("/tmp/shot.png")])
+                .unwrap(),
+        );
+        assert_eq!(mpv_socket.client_name().unwrap(), "ipc-1");
+        assert!(mpv_socket.async_commands.is_empty());
+    }
+
+    #[test]
+    fn batch_collects_replies_by_id() {
+        let (mut mpv_socket, output) = mock(&[
+            r#"{"request_id":4,"error":"property unavailable"}"#,
+            r#"{"event":"seek"}"#,
+            r#"{"request_id":3,"error":"success","data":80.0}"#,
+            r#"{"request_id":1,"error":"success","data":"/tmp/shot.png"}"#,
+            r#"{"event":"playback-restart"}"#,
+            r#"{"request_id":2,"error":"success"}"#,
+        ]);
+
+        let screenshot = mpv_socket
+            .command_async("screenshot-to-file", vec![Value::from("/tmp/shot.png")])
+            .unwrap();
+        let mut results = mpv_socket
+            .batch()
+            .set(Property::Volume, 80.0)
+            .get(Property::Volume)
+            .get(Property::Duration)
+            .send()
+            .unwrap();
+        assert_eq!(results.len(), 3);
+        assert_eq!(results.take::<Value>(0).unwrap(), Value::Null);
+        assert_eq!(results.take::<f64>(1).unwrap(), 80.0);
+        assert!(matches!(
+            results.take::<f64>(2),
+            Err(Error::Mpv(MpvErrorCode::PropertyUnavailable))
+        ));
+
+        let path: String = screenshot.wait(&mut mpv_socket).unwrap();
+        assert_eq!(path, "/tmp/shot.png");
+        let events = mpv_socket.events().collect::<Result<Vec<_>>>().unwrap();
+        assert_eq!(events, vec![Event::Seek, Event::PlaybackRestart]);
+        assert!(String::from_utf8_lossy(&output.borrow()).ends_with(concat!(
+            "{\"command\":[\"set_property\",\"volume\",80.0],\"request_id\":2}\n",
+            "{\"command\":[\"get_property\",\"volume\"],\"request_id\":3}\n",
+            "{\"command\":[\"get_property\",\"duration\"],\"request_id\":4}\n",
+        )));
+    }
+
+    #[test]
+    fn mpv_error_response() {
+        let (mut mpv_socket, _output) = mock(&[
+            r#"{"request_id":1,"error":"property unavailable"}"#,
+            r#"{"request_id":2,"error":"some future error"}"#,
+        ]);
+
+        let error = mpv_socket
+            .get_property::<f64>(Property::Duration)
+            .unwrap_err();
+        assert!(matches!(
+            error,
+            Error::Mpv(MpvErrorCode::PropertyUnavailable)
+        ));
+        let error = mpv_socket
+            .get_property::<f64>(Property::Duration)
+            .unwrap_err();
+        assert!(
+            matches!(error, Error::Mpv(MpvErrorCode::Other(code)) if code == "some future error")
+        );
+    }
+
+    #[test]
+    fn type_mismatch() {
+        let (mut mpv_socket, _output) =
+            mock(&[r#"{"request_id":1,"error":"success","data":"yes"}"#]);
+
+        let error = mpv_socket
+            .get_property::<bool>(Property::Pause)
+            .unwrap_err();
+        assert!(matches!(
+            error,
+            Error::TypeMismatch { expected: "bool", got: Value::String(got) } if got == "yes"
+        ));
+    }
+
+    #[test]
+    fn closed_after_end_of_stream() {
+        let (mut mpv_socket, _output) = mock(&[r#"{"event":"shutdown"}"#]);
+
+        assert!(mpv_socket.events().next().unwrap().is_ok());
+        assert!(matches!(mpv_socket.client_name(), Err(Error::Closed)));
+    }
+
+    #[test]
+    fn request_timeout_while_reply_is_missing() {
+        let (mut mpv_socket, _output) = mock_chunks(&[Some("{\"event\":\"seek\"}\n")], true);
+        mpv_socket.request_timeout = Some(Duration::from_millis(10));
+
+        assert!(matches!(mpv_socket.client_name(), Err(Error::Timeout)));
+        assert_eq!(mpv_socket.pending_events.len(), 1);
+    }
+
+    #[test]
+    fn read_timeout_without_request_timeout() {
+        let (mut mpv_socket, _output) = mock_chunks(&[], true);
+        mpv_socket.read_timeout = Some(Duration::from_millis(10));
+
+        assert!(matches!(mpv_socket.client_name(), Err(Error::Timeout)));
+    }
+
+    #[test]
+    fn event_timeout() {
+        let (mut mpv_socket, _output) = mock_chunks(&[Some("{\"event\":\"seek\"}\n")], true);
+        mpv_socket.event_timeout = Some(Duration::from_millis(10));
+
+        let mut events = mpv_socket.events();
+        assert_eq!(events.next().unwrap().unwrap(), Event::Seek);
+        assert!(matches!(events.next(), Some(Err(Error::Timeout))));
+    }
+
+    #[test]
+    fn events_wait_past_read_timeout_without_event_timeout() {
+        let (stream, _output) = mock_stream(
+            &[
+                Some("{\"event\":\"seek\"}\n"),
+                None,
+                None,
+                Some("{\"event\":\"playback-restart\"}\n"),
+            ],
+            false,
+        );
+        let mut mpv_socket = MpvSocket::builder()
+            .read_timeout(Some(Duration::from_millis(0)))
+            .socket(Box::new(stream));
+
+        let events = mpv_socket.events().collect::<Result<Vec<_>>>().unwrap();
+        assert_eq!(events, vec![Event::Seek, Event::PlaybackRestart]);
+    }
+
+    #[test]
+    fn partial_line_is_kept_after_read_timeout() {
+        let (mut mpv_socket, _output) = mock_chunks(
+            &[
+                Some("{\"request_id\":1,\"err"),
+                None,
+                Some("or\":\"success\",\"data\":\"ipc-1\"}\n"),
+            ],
+            false,
+        );
+        mpv_socket.request_timeout = Some(Duration::from_secs(10));
+
+        assert_eq!(mpv_socket.client_name().unwrap(), "ipc-1");
+    }
+
+    /// Read the next request sent to the mpv end of a socket pair.
+    #[cfg(unix)]
+    fn read_request(reader: &mut impl BufRead) -> Value {
+        let mut request = String::new();
+        reader.read_line(&mut request).unwrap();
+        serde_json::from_str(&request).unwrap()
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn client_routes_out_of_order_replies_and_events() {
+        use std::os::unix::net::UnixStream;
+
+        let (socket, mpv_end) = UnixStream::pair().unwrap();
+        let client = MpvClient::from_unix_stream(socket, Some(Duration::from_secs(10))).unwrap();
+        let mut events = client.events().unwrap();
+
+        let mpv = std::thread::spawn(move || {
+            let mut reader = BufReader::new(mpv_end.try_clone().unwrap());
+            let mut writer = mpv_end;
+            let first = read_request(&mut reader);
+            let second = read_request(&mut reader);
+            let (name, volume) = if first["command"][0] == "client_name" {
+                (first, second)
+            } else {
+                (second, first)
+            };
+            write!(
+                writer,
+                concat!(
+                    "{{\"event\":\"seek\"}}\n",
+                    "{{\"request_id\":{},\"error\":\"success\",\"data\":50.0}}\n",
+                    "{{\"event\":\"playback-restart\"}}\n",
+                    "{{\"request_id\":{},\"error\":\"success\",\"data\":\"ipc-1\"}}\n",
+                ),
+                volume["request_id"], name["request_id"],
+            )
+            .unwrap();
+        });
+
+        let name_client = client.clone();
+        let name = std::thread::spawn(move || name_client.client_name());
+        let volume: f64 = client.get_property(Property::Volume).unwrap();
+        assert_eq!(volume, 50.0);
+        assert_eq!(name.join().unwrap().unwrap(), "ipc-1");
+        mpv.join().unwrap();
+
+        assert_eq!(events.next(), Some(Event::Seek));
+        assert_eq!(events.next(), Some(Event::PlaybackRestart));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn client_skips_property_changes_without_data() {
+        use std::os::unix::net::UnixStream;
+
+        let (socket, mpv_end) = UnixStream::pair().unwrap();
+        let client = MpvClient::from_unix_stream(socket, Some(Duration::from_secs(10))).unwrap();
+
+        let mpv = std::thread::spawn(move || {
+            let mut reader = BufReader::new(mpv_end.try_clone().unwrap());
+            let mut writer = mpv_end;
+            let observe = read_request(&mut reader);
+            write!(
+                writer,
+                concat!(
+                    "{{\"request_id\":{},\"error\":\"success\"}}\n",
+                    "{{\"event\":\"property-change\",\"id\":1,\"name\":\"duration\"}}\n",
+                    "{{\"event\":\"property-change\",\"id\":1,\"name\":\"duration\",\"data\":60.0}}\n",
+                ),
+                observe["request_id"],
+            )
+            .unwrap();
+            writer
+        });
+
+        let mut durations = client.observe_property::<f64>(Property::Duration).unwrap();
+        assert_eq!(durations.next().unwrap().unwrap(), 60.0);
+        drop(mpv.join().unwrap());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn client_request_timeout() {
+        use std::os::unix::net::UnixStream;
+
+        let (socket, _mpv_end) = UnixStream::pair().unwrap();
+        let client = MpvClient::from_unix_stream(socket, Some(Duration::from_millis(10))).unwrap();
+
+        assert!(matches!(client.client_name(), Err(Error::Timeout)));
+    }
+
+    #[cfg(all(unix, feature = "tokio"))]
+    fn block_on<F: std::future::Future>(future: F) -> F::Output {
+        tokio::runtime::Builder::new_current_thread()
+            .enable_time()
+            .build()
+            .unwrap()
+            .block_on(future)
+    }
+
+    #[cfg(all(unix, feature = "tokio"))]
+    #[test]
+    fn async_client_routes_out_of_order_replies_and_events() {
+        use futures_core::Stream;
+        use std::pin::Pin;
+        use tokio::io::{AsyncBufReadExt, AsyncWriteExt};
+
+        block_on(async {
+            let (client_end, mpv_end) = tokio::io::duplex(4096);
+            let (reader, writer) = tokio::io::split(client_end);
+            let client = async_client::MpvClient::new(reader, writer, None);
+            let mut events = client.events().unwrap();
+
+            let mpv = tokio::spawn(async move {
+                let (reader, mut writer) = tokio::io::split(mpv_end);
+                let mut lines = tokio::io::BufReader::new(reader).lines();
+                let first: Value =
+                    serde_json::from_str(&lines.next_line().await.unwrap().unwrap()).unwrap();
+                let second: Value =
+                    serde_json::from_str(&lines.next_line().await.unwrap().unwrap()).unwrap();
+                let (name, volume) = if first["command"][0] == "client_name" {
+                    (first, second)
+                } else {
+                    (second, first)
+                };
+                let output = format!(
+                    concat!(
+                        "{{\"event\":\"seek\"}}\n",
+                        "{{\"request_id\":{},\"error\":\"success\",\"data\":50.0}}\n",
+                        "{{\"event\":\"playback-restart\"}}\n",
+                        "{{\"request_id\":{},\"error\":\"success\",\"data\":\"ipc-1\"}}\n",
+                    ),
+                    volume["request_id"], name["request_id"],
+                );
+                writer.write_all(output.as_bytes()).await.unwrap();
+                (lines, writer)
+            });
+
+            let name_client = client.clone();
+            let name = tokio::spawn(async move { name_client.client_name().await });
+            let volume: f64 = client.get_property(Property::Volume).await.unwrap();
+            assert_eq!(volume, 50.0);
+            assert_eq!(name.await.unwrap().unwrap(), "ipc-1");
+            let _mpv_end = mpv.await.unwrap();
+
+            let first = std::future::poll_fn(|cx| Pin::new(&mut events).poll_next(cx));
+            assert_eq!(first.await, Some(Event::Seek));
+            let second = std::future::poll_fn(|cx| Pin::new(&mut events).poll_next(cx));
+            assert_eq!(second.await, Some(Event::PlaybackRestart));
+        });
+    }
+
+    #[cfg(all(unix, feature = "tokio"))]
+    #[test]
+    fn async_client_cancelled_request_is_forgotten() {
+        use std::future::Future;
+        use std::task::{Context, Waker};
+        use tokio::io::{AsyncBufReadExt, AsyncWriteExt};
+
+        block_on(async {
+            let (client_end, mpv_end) = tokio::io::duplex(4096);
+            let (reader, writer) = tokio::io::split(client_end);
+            let client = async_client::MpvClient::new(reader, writer, None);
+
+            // Cancel the request after it was queued, like a timeout would.
+            let mut cancelled = Box::pin(client.client_name());
+            let mut cx = Context::from_waker(Waker::noop());
+            assert!(cancelled.as_mut().poll(&mut cx).is_pending());
+            drop(cancelled);
+            assert_eq!(client.num_pending_replies(), 0);
+
+            let mpv = tokio::spawn(async move {
+                let (reader, mut writer) = tokio::io::split(mpv_end);
+                let mut lines = tokio::io::BufReader::new(reader).lines();
+                let first = lines.next_line().await.unwrap().unwrap();
+                let second = lines.next_line().await.unwrap().unwrap();
+                writer
+                    .write_all(
+                        concat!(
+                            "{\"request_id\":1,\"error\":\"success\",\"data\":\"ipc-1\"}\n",
+                            "{\"request_id\":2,\"error\":\"success\",\"data\":true}\n",
+                        )
+                        .as_bytes(),
+                    )
+                    .await
+                    .unwrap();
+                (first, second, lines, writer)
+            });
+
+            assert!(client.get_property::<bool>(Property::Pause).await.unwrap());
+            let (first, second, _lines, _writer) = mpv.await.unwrap();
+            assert_eq!(first, "{\"command\":[\"client_name\"],\"request_id\":1}");
+            assert_eq!(
+                second,
+                "{\"command\":[\"get_property\",\"pause\"],\"request_id\":2}"
+            );
+            assert_eq!(client.num_pending_replies(), 0);
+        });
+    }
+
+    #[cfg(all(unix, feature = "tokio"))]
+    #[test]
+    fn async_client_request_timeout() {
+        block_on(async {
+            let (client_end, _mpv_end) = tokio::io::duplex(4096);
+            let (reader, writer) = tokio::io::split(client_end);
+            let client =
+                async_client::MpvClient::new(reader, writer, Some(Duration::from_millis(10)));
+
+            assert!(matches!(client.client_name().await, Err(Error::Timeout)));
+            assert_eq!(client.num_pending_replies(), 0);
+        });
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn from_unix_stream() {
+        use std::os::unix::net::UnixStream;
+
+        let (socket, mut mpv_end) = UnixStream::pair().unwrap();
+        let mpv = std::thread::spawn(move || {
+            let mut request = String::new();
+            BufReader::new(&mut mpv_end)
+                .read_line(&mut request)
+                .unwrap();
+            mpv_end
+                .write_all(b"{\"request_id\":1,\"error\":\"success\",\"data\":\"ipc-1\"}\n")
+                .unwrap();
+            request
+        });
+
+        let mut mpv_socket = MpvSocket::from_unix_stream(socket);
+        assert_eq!(mpv_socket.client_name().unwrap(), "ipc-1");
+        assert_eq!(
+            mpv.join().unwrap(),
+            "{\"command\":[\"client_name\"],\"request_id\":1}\n"
+        );
+    }
 }
@@ -0,0 +1,80 @@
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::io::{Read, Write};
+use std::rc::Rc;
+
+use crate::MpvSocket;
+
+/// In-memory stream which replays the given mpv output
+/// and records everything written to it.
+pub(crate) struct MockStream {
+    /// Chunks of output, `None` simulates a read timeout.
+    input: VecDeque<Option<Vec<u8>>>,
+    /// Whether reads time out instead of ending the stream after the last chunk.
+    block_at_end: bool,
+    output: Rc<RefCell<Vec<u8>>>,
+}
+
+impl Read for MockStream {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        match self.input.pop_front() {
+            Some(Some(mut chunk)) => {
+                let len = chunk.len().min(buf.len());
+                buf[..len].copy_from_slice(&chunk[..len]);
+                if len < chunk.len() {
+                    self.input.push_front(Some(chunk.split_off(len)));
+                }
+                Ok(len)
+            }
+            Some(None) => Err(std::io::ErrorKind::WouldBlock.into()),
+            None if self.block_at_end => Err(std::io::ErrorKind::WouldBlock.into()),
+            None => Ok(0),
+        }
+    }
+}
+
+impl Write for MockStream {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.output.borrow_mut().write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Create an `MpvSocket` which receives the given lines,
+/// and a handle to the requests sent by it.
+pub(crate) fn mock(lines: &[&str]) -> (MpvSocket, Rc<RefCell<Vec<u8>>>) {
+    let mut input = lines.join("\n");
+    input.push('\n');
+    mock_chunks(&[Some(&input)], false)
+}
+
+/// Create an `MpvSocket` which receives the given chunks,
+/// where `None` simulates a read timeout.
+pub(crate) fn mock_chunks(
+    chunks: &[Option<&str>],
+    block_at_end: bool,
+) -> (MpvSocket, Rc<RefCell<Vec<u8>>>) {
+    let (stream, output) = mock_stream(chunks, block_at_end);
+    (MpvSocket::from_stream(stream), output)
+}
+
+/// Create a stream which receives the given chunks,
+/// where `None` simulates a read timeout.
+pub(crate) fn mock_stream(
+    chunks: &[Option<&str>],
+    block_at_end: bool,
+) -> (MockStream, Rc<RefCell<Vec<u8>>>) {
+    let output = Rc::new(RefCell::new(Vec::new()));
+    let stream = MockStream {
+        input: chunks
+            .iter()
+            .map(|chunk| chunk.map(|chunk| chunk.as_bytes().to_vec()))
+            .collect(),
+        block_at_end,
+        output: Rc::clone(&output),
+    };
+    (stream, output)
+}
@@ -0,0 +1,57 @@
+use std::sync::{Arc, Mutex};
+
+use crate::PropertyName;
+
+pub(crate) type DroppedObservations = Arc<Mutex<Vec<i64>>>;
+
+/// Handle to an observed property.
+///
+/// Returned by [`MpvSocket::observe`].
+/// Changes of the property are delivered as [`Event::PropertyChange`]
+/// while iterating [`MpvSocket::events`].
+///
+/// The property is unobserved when the handle is dropped,
+/// the request to mpv is sent before the next command or event is read.
+/// Changes of the property which are still received afterwards are discarded.
+///
+/// [`MpvSocket::observe`]: ./struct.MpvSocket.html#method.observe
+/// [`MpvSocket::events`]: ./struct.MpvSocket.html#method.events
+/// [`Event::PropertyChange`]: ./event/enum.Event.html#variant.PropertyChange
+#[derive(Debug)]
+pub struct ObservationHandle {
+    id: i64,
+    property: PropertyName,
+    dropped: DroppedObservations,
+}
+
+impl ObservationHandle {
+    pub(crate) fn new(
+        id: i64,
+        property: PropertyName,
+        dropped: DroppedObservations,
+    ) -> ObservationHandle {
+        ObservationHandle {
+            id,
+            property,
+            dropped,
+        }
+    }
+
+    /// The observation id, which is negative and unique per socket.
+    pub fn id(&self) -> i64 {
+        self.id
+    }
+
+    /// The observed property.
+    pub fn property(&self) -> &PropertyName {
+        &self.property
+    }
+}
+
+impl Drop for ObservationHandle {
+    fn drop(&mut self) {
+        if let Ok(mut dropped) = self.dropped.lock() {
+            dropped.push(self.id);
+        }
+    }
+}
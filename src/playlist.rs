@@ -0,0 +1,36 @@
+use serde::Deserialize;
+
+use crate::property::deserialize_value;
+use crate::{Result, TryFromValue, Value};
+
+/// Entry of the [`Property::Playlist`].
+///
+/// [`Property::Playlist`]: ./enum.Property.html#variant.Playlist
+#[derive(Debug, Clone, Deserialize, PartialEq)]
+pub struct PlaylistEntry {
+    /// Filename of the entry.
+    pub filename: String,
+    /// Name of the entry.
+    ///
+    /// Only available if the playlist file contains such fields,
+    /// and only if mpv's parser supports it for the given playlist format.
+    pub title: Option<String>,
+    /// Unique ID for this entry.
+    ///
+    /// This is an automatically assigned integer ID
+    /// that is unique for the entire life time of the current mpv core instance.
+    /// Other commands, events, etc. use this as `playlist_entry_id` fields.
+    pub id: Option<i64>,
+    /// `true` if the `playlist-current-pos` property points to this entry.
+    #[serde(default)]
+    pub current: bool,
+    /// `true` if the `playlist-playing-pos` property points to this entry.
+    #[serde(default)]
+    pub playing: bool,
+}
+
+impl TryFromValue for PlaylistEntry {
+    fn try_from(value: Value) -> Result<PlaylistEntry> {
+        deserialize_value(value)
+    }
+}
@@ -0,0 +1,172 @@
+use std::ffi::OsStr;
+use std::os::unix::io::AsRawFd;
+use std::os::unix::net::UnixStream;
+use std::os::unix::process::CommandExt;
+use std::process::{Child, Command, ExitStatus};
+
+use crate::{MpvSocket, Result};
+
+/// A spawned mpv process.
+///
+/// mpv is started with `--input-ipc-client=fd://N` and talks over one end of a socket pair,
+/// so no socket path is created and the connection is usable as soon as mpv is spawned.
+///
+/// Created together with its connection by [`spawn`] or [`MpvSocketBuilder::spawn`].
+/// Like [`std::process::Child`], dropping it neither kills nor waits for the process.
+///
+/// Requires the `process` feature and a Unix platform.
+///
+/// # Example
+///
+/// ```no_run
+/// use mpv_socket::{MpvProcess, Property};
+///
+/// # fn main() -> mpv_socket::Result<()> {
+/// let (mut process, mut mpv) = MpvProcess::spawn(&["--idle=yes", "--no-terminal"])?;
+/// mpv.set_property(Property::Pause, true)?;
+///
+/// process.kill()?;
+/// let status = process.wait()?;
+/// println!("mpv exited with: {}", status);
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [`spawn`]: #method.spawn
+/// [`MpvSocketBuilder::spawn`]: ./struct.MpvSocketBuilder.html#method.spawn
+/// [`std::process::Child`]: https://doc.rust-lang.org/std/process/struct.Child.html
+#[derive(Debug)]
+pub struct MpvProcess {
+    child: Child,
+}
+
+impl MpvProcess {
+    /// Spawns `mpv` from the `PATH` with the given arguments and connects to it.
+    ///
+    /// The `--input-ipc-client` option is added before the given arguments,
+    /// so they may end with `--` followed by files.
+    ///
+    /// Use [`MpvSocketBuilder::spawn`] to configure the command or the timeouts.
+    ///
+    /// [`MpvSocketBuilder::spawn`]: ./struct.MpvSocketBuilder.html#method.spawn
+    pub fn spawn<I, S>(args: I) -> Result<(MpvProcess, MpvSocket)>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<OsStr>,
+    {
+        MpvSocket::builder().spawn_with_args(Command::new("mpv"), args)
+    }
+
+    /// Returns the OS-assigned process identifier.
+    pub fn id(&self) -> u32 {
+        self.child.id()
+    }
+
+    /// Waits for mpv to exit and returns its exit status.
+    pub fn wait(&mut self) -> Result<ExitStatus> {
+        Ok(self.child.wait()?)
+    }
+
+    /// Returns the exit status if mpv has exited, without blocking.
+    pub fn try_wait(&mut self) -> Result<Option<ExitStatus>> {
+        Ok(self.child.try_wait()?)
+    }
+
+    /// Kills mpv.
+    ///
+    /// Use [`wait`] afterwards to release the resources of the process.
+    /// Prefer the `quit` command to let mpv exit on its own.
+    ///
+    /// [`wait`]: #method.wait
+    pub fn kill(&mut self) -> Result<()> {
+        Ok(self.child.kill()?)
+    }
+}
+
+/// Spawns the command with one end of a new socket pair
+/// and returns the process together with the other end.
+///
+/// The `--input-ipc-client` option is added to the arguments of the command
+/// before the given `args`.
+pub(crate) fn spawn<I, S>(
+    mut command: Command,
+    args: I,
+) -> std::io::Result<(MpvProcess, UnixStream)>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<OsStr>,
+{
+    let (socket, mpv_socket) = UnixStream::pair()?;
+    let fd = mpv_socket.as_raw_fd();
+    command.arg(format!("--input-ipc-client=fd://{}", fd));
+    command.args(args);
+
+    // SAFETY: `fcntl` is async-signal-safe and the closure does not allocate.
+    unsafe {
+        command.pre_exec(move || {
+            // Sockets are created with `FD_CLOEXEC`,
+            // clear it so that mpv inherits its end of the pair.
+            let flags = libc::fcntl(fd, libc::F_GETFD);
+            if flags == -1 || libc::fcntl(fd, libc::F_SETFD, flags & !libc::FD_CLOEXEC) == -1 {
+                return Err(std::io::Error::last_os_error());
+            }
+            Ok(())
+        });
+    }
+
+    log::info!("spawning: {:?}", command);
+    let child = command.spawn()?;
+    drop(mpv_socket);
+
+    Ok((MpvProcess { child }, socket))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Spawns a shell script which answers a single request like mpv.
+    ///
+    /// The appended `--input-ipc-client` argument becomes `$0` of the script.
+    #[test]
+    fn spawn_with_inherited_socket() {
+        let mut command = Command::new("sh");
+        command.arg("-c").arg(
+            r#"fd=${0#--input-ipc-client=fd://}
+read -r request <&"$fd"
+echo '{"request_id":1,"error":"success","data":"ipc-1"}' >&"$fd""#,
+        );
+
+        let (mut process, mut mpv) = MpvSocket::builder().spawn(command).unwrap();
+        assert_eq!(mpv.client_name().unwrap(), "ipc-1");
+        assert!(process.wait().unwrap().success());
+    }
+
+    /// Like `spawn_with_inherited_socket`, but the script replies with its arguments.
+    #[test]
+    fn spawn_adds_ipc_client_before_args() {
+        let mut command = Command::new("sh");
+        command.arg("-c").arg(
+            r#"fd=${0#--input-ipc-client=fd://}
+read -r request <&"$fd"
+echo "{\"request_id\":1,\"error\":\"success\",\"data\":\"$*\"}" >&"$fd""#,
+        );
+
+        let (mut process, mut mpv) = MpvSocket::builder()
+            .spawn_with_args(command, ["--", "a.mkv"])
+            .unwrap();
+        assert_eq!(mpv.client_name().unwrap(), "-- a.mkv");
+        assert!(process.wait().unwrap().success());
+    }
+
+    #[test]
+    fn spawn_rejects_end_of_options() {
+        let mut command = Command::new("mpv");
+        command.args(["--idle=yes", "--", "a.mkv"]);
+
+        assert!(matches!(
+            MpvSocket::builder().spawn(command),
+            Err(crate::Error::Other(_))
+        ));
+    }
+}
@@ -1,11 +1,13 @@
 #![allow(deprecated)]
 
 use std::fmt;
+use std::hash::{Hash, Hasher};
 
-use serde::Deserialize;
+use serde::de::IntoDeserializer;
+use serde::{Deserialize, Deserializer};
 pub use serde_json::{Map, Value};
 
-use crate::Result;
+use crate::{Error, Result};
 
 /// Properties are used to set mpv options during runtime,
 /// or to query arbitrary information.
@@ -14,7 +16,7 @@ use crate::Result;
 /// to indicate whether the property is generally writable.
 ///
 /// Official documentation: [https://mpv.io/manual/master/#properties](https://mpv.io/manual/master/#properties)
-#[derive(Debug, Copy, Clone, Deserialize, Eq, PartialEq)]
+#[derive(Debug, Copy, Clone, Deserialize, Eq, PartialEq, Hash)]
 #[serde(rename_all = "kebab-case")]
 pub enum Property {
     /// Factor multiplied with speed at which the player attempts to play the file.
@@ -44,6 +46,7 @@ pub enum Property {
     /// Like the filename property, but if the text contains a ., strip all text after the last ..
     ///
     /// Usually this removes the file extension.
+    #[serde(rename = "filename/no-ext")]
     FilenameNoExt,
     /// Length in bytes of the source file/stream.
     ///
@@ -180,42 +183,42 @@ pub enum Property {
     /*
 
     */
-    // chapter (RW),
-    // /// Current chapter number. The number of the first chapter is 0.
-    // edition (RW),
-    //
-    // /// Current MKV edition number. Setting this property to a different value will restart playback. The number of the first edition is 0.
-    //
-    // /// Before mpv 0.31.0, this showed the actual edition selected at runtime, if you didn't set the option or property manually. With mpv 0.31.0 and later, this strictly returns the user-set option or property value, and the current-edition property was added to return the runtime selected edition (this matters with --edition=auto, the default).
-    // current-edition,
-    // /// Currently selected edition. This property is unavailable if no file is loaded, or the file has no editions. (Matroska files make a difference between having no editions and a single edition, which will be reflected by the property, although in practice it does not matter.)
-    // chapters,
-    // /// Number of chapters.
-    // editions,
-    // /// Number of MKV editions.
-    // edition-list,
-    //
-    // /// List of editions, current entry marked. Currently, the raw property value is useless.
-    //
-    // /// This has a number of sub-properties. Replace N with the 0-based edition index.
-    //
-    // /// edition-list/count
-    // ///     Number of editions. If there are no editions, this can be 0 or 1 (1 if there's a useless dummy edition).
-    // /// edition-list/N/id
-    // ///     Edition ID as integer. Use this to set the edition property. Currently, this is the same as the edition index.
-    // /// edition-list/N/default
-    // ///     yes if this is the default edition, no otherwise.
-    // /// edition-list/N/title
-    // ///     Edition title as stored in the file. Not always available.
-    //
-    // /// When querying the property with the client API using MPV_FORMAT_NODE, or with Lua mp.get_property_native, this will return a mpv_node with the following contents:
-    //
-    // /// MPV_FORMAT_NODE_ARRAY
-    // ///     MPV_FORMAT_NODE_MAP (for each edition)
-    // ///         "id"                MPV_FORMAT_INT64
-    // ///         "title"             MPV_FORMAT_STRING
-    // ///         "default"           MPV_FORMAT_FLAG
-    //
+    /// **(RW)** Current chapter number.
+    ///
+    /// The number of the first chapter is 0.
+    Chapter,
+    /// **(RW)** Current MKV edition number.
+    ///
+    /// Setting this property to a different value will restart playback.
+    /// The number of the first edition is 0.
+    ///
+    /// Before mpv 0.31.0, this showed the actual edition selected at runtime,
+    /// if you didn't set the option or property manually.
+    /// With mpv 0.31.0 and later, this strictly returns the user-set option or property value,
+    /// and the `current-edition` property was added to return the runtime selected edition
+    /// (this matters with `--edition=auto`, the default).
+    Edition,
+    /// Currently selected edition.
+    ///
+    /// This property is unavailable if no file is loaded, or the file has no editions.
+    /// (Matroska files make a difference between having no editions and a single edition,
+    /// which will be reflected by the property, although in practice it does not matter.)
+    CurrentEdition,
+    /// Number of chapters.
+    Chapters,
+    /// Number of MKV editions.
+    Editions,
+    /// List of editions, current entry marked.
+    ///
+    /// Can be retrieved as a list of [`Edition`] values,
+    /// see [`MpvSocket::editions`].
+    ///
+    /// [`Edition`]: ./struct.Edition.html
+    /// [`MpvSocket::editions`]: ./struct.MpvSocket.html#method.editions
+    EditionList,
+    /*
+
+    */
     // metadata,
     //
     // /// Metadata key/value pairs.
@@ -246,13 +249,22 @@ pub enum Property {
     //
     // filtered-metadata,
     // /// Like metadata, but includes only fields listed in the --display-tags option. This is the same set of tags that is printed to the terminal.
-    // chapter-metadata,
-    //
-    // /// Metadata of current chapter. Works similar to metadata property. It also allows the same access methods (using sub-properties).
-    //
-    // /// Per-chapter metadata is very rare. Usually, only the chapter name (title) is set.
-    //
-    // /// For accessing other information, like chapter start, see the chapter-list property.
+    /*
+
+    */
+    /// Metadata of current chapter.
+    ///
+    /// Works similar to the `metadata` property.
+    /// It also allows the same access methods (using sub-properties).
+    ///
+    /// Per-chapter metadata is very rare.
+    /// Usually, only the chapter name (title) is set.
+    ///
+    /// For accessing other information, like chapter start, see the `chapter-list` property.
+    ChapterMetadata,
+    /*
+
+    */
     // vf-metadata/<filter-label>,
     //
     // /// Metadata added by video filters. Accessed by the filter label, which, if not explicitly specified using the @filter-label: syntax, will be <filter-name>NN.
@@ -570,174 +582,102 @@ pub enum Property {
     // /// Return the current subtitle start time (in seconds). If there's multiple current subtitles, returns the first start time. If no current subtitle is present null is returned instead.
     // sub-end,
     // /// Return the current subtitle end time (in seconds). If there's multiple current subtitles, return the last end time. If no current subtitle is present, or if it's present but has unknown or incorrect duration, null is returned instead.
-    // playlist-pos (RW),
-    //
-    // /// Current position on playlist. The first entry is on position 0. Writing to this property may start playback at the new position.
-    //
-    // /// In some cases, this is not necessarily the currently playing file. See explanation of current and playing flags in playlist.
-    //
-    // /// If there the playlist is empty, or if it's non-empty, but no entry is "current", this property returns -1. Likewise, writing -1 will put the player into idle mode (or exit playback if idle mode is not enabled). If an out of range index is written to the property, this behaves as if writing -1. (Before mpv 0.33.0, instead of returning -1, this property was unavailable if no playlist entry was current.)
-    //
-    // /// Writing the current value back to the property is subject to change. Currently, it will restart playback of the playlist entry. But in the future, writing the current value will be ignored. Use the playlist-play-index command to get guaranteed behavior.
-    // playlist-pos-1 (RW),
-    // /// Same as playlist-pos, but 1-based.
-    // playlist-current-pos (RW),
-    //
-    // /// Index of the "current" item on playlist. This usually, but not necessarily, the currently playing item (see playlist-playing-pos). Depending on the exact internal state of the player, it may refer to the playlist item to play next, or the playlist item used to determine what to play next.
-    //
-    // /// For reading, this is exactly the same as playlist-pos.
-    //
-    // /// For writing, this only sets the position of the "current" item, without stopping playback of the current file (or starting playback, if this is done in idle mode). Use -1 to remove the current flag.
-    //
-    // /// This property is only vaguely useful. If set during playback, it will typically cause the playlist entry after it to be played next. Another possibly odd observable state is that if playlist-next is run during playback, this property is set to the playlist entry to play next (unlike the previous case). There is an internal flag that decides whether the current playlist entry or the next one should be played, and this flag is currently inaccessible for API users. (Whether this behavior will kept is possibly subject to change.)
-    // playlist-playing-pos,
-    //
-    // /// Index of the "playing" item on playlist. A playlist item is "playing" if it's being loaded, actually playing, or being unloaded. This property is set during the MPV_EVENT_START_FILE (start-file) and the MPV_EVENT_START_END (end-file) events. Outside of that, it returns -1. If the playlist entry was somehow removed during playback, but playback hasn't stopped yet, or is in progress of being stopped, it also returns -1. (This can happen at least during state transitions.)
-    //
-    // /// In the "playing" state, this is usually the same as playlist-pos, except during state changes, or if playlist-current-pos was written explicitly.
-    // playlist-count,
-    // /// Number of total playlist entries.
-    // playlist,
-    //
-    // /// Playlist, current entry marked. Currently, the raw property value is useless.
-    //
-    // /// This has a number of sub-properties. Replace N with the 0-based playlist entry index.
-    //
-    // /// playlist/count
-    // ///     Number of playlist entries (same as playlist-count).
-    // /// playlist/N/filename
-    // ///     Filename of the Nth entry.
-    // /// playlist/N/playing
-    // ///     yes if the playlist-playing-pos property points to this entry, unavailable or no otherwise.
-    // /// playlist/N/current
-    // ///     yes if the playlist-current-pos property points to this entry, unavailable or no otherwise.
-    // /// playlist/N/title
-    // ///     Name of the Nth entry. Only available if the playlist file contains such fields, and only if mpv's parser supports it for the given playlist format.
-    // /// playlist/N/id
-    // ///     Unique ID for this entry. This is an automatically assigned integer ID that is unique for the entire life time of the current mpv core instance. Other commands, events, etc. use this as playlist_entry_id fields.
-    //
-    // /// When querying the property with the client API using MPV_FORMAT_NODE, or with Lua mp.get_property_native, this will return a mpv_node with the following contents:
-    //
-    // /// MPV_FORMAT_NODE_ARRAY
-    // ///     MPV_FORMAT_NODE_MAP (for each playlist entry)
-    // ///         "filename"  MPV_FORMAT_STRING
-    // ///         "current"   MPV_FORMAT_FLAG (might be missing; since mpv 0.7.0)
-    // ///         "playing"   MPV_FORMAT_FLAG (same)
-    // ///         "title"     MPV_FORMAT_STRING (optional)
-    // ///         "id"        MPV_FORMAT_INT64
-    //
-    // track-list,
-    //
-    // /// List of audio/video/sub tracks, current entry marked. Currently, the raw property value is useless.
-    //
-    // /// This has a number of sub-properties. Replace N with the 0-based track index.
-    //
-    // /// track-list/count
-    // ///     Total number of tracks.
-    // /// track-list/N/id
-    // ///     The ID as it's used for -sid/--aid/--vid. This is unique within tracks of the same type (sub/audio/video), but otherwise not.
-    // /// track-list/N/type
-    // ///     String describing the media type. One of audio, video, sub.
-    // /// track-list/N/src-id
-    // ///     Track ID as used in the source file. Not always available. (It is missing if the format has no native ID, if the track is a pseudo-track that does not exist in this way in the actual file, or if the format is handled by libavformat, and the format was not whitelisted as having track IDs.)
-    // /// track-list/N/title
-    // ///     Track title as it is stored in the file. Not always available.
-    // /// track-list/N/lang
-    // ///     Track language as identified by the file. Not always available.
-    // /// track-list/N/albumart
-    // ///     yes if this is a video track that consists of a single picture, no or unavailable otherwise. This is used for video tracks that are really attached pictures in audio files.
-    // /// track-list/N/default
-    // ///     yes if the track has the default flag set in the file, no otherwise.
-    // /// track-list/N/forced
-    // ///     yes if the track has the forced flag set in the file, no otherwise.
-    // /// track-list/N/codec
-    // ///     The codec name used by this track, for example h264. Unavailable in some rare cases.
-    // /// track-list/N/external
-    // ///     yes if the track is an external file, no otherwise. This is set for separate subtitle files.
-    // /// track-list/N/external-filename
-    // ///     The filename if the track is from an external file, unavailable otherwise.
-    // /// track-list/N/selected
-    // ///     yes if the track is currently decoded, no otherwise.
-    // /// track-list/N/ff-index
-    // ///     The stream index as usually used by the FFmpeg utilities. Note that this can be potentially wrong if a demuxer other than libavformat (--demuxer=lavf) is used. For mkv files, the index will usually match even if the default (builtin) demuxer is used, but there is no hard guarantee.
-    // /// track-list/N/decoder-desc
-    // ///     If this track is being decoded, the human-readable decoder name,
-    // /// track-list/N/demux-w, track-list/N/demux-h
-    // ///     Video size hint as indicated by the container. (Not always accurate.)
-    // /// track-list/N/demux-channel-count
-    // ///     Number of audio channels as indicated by the container. (Not always accurate - in particular, the track could be decoded as a different number of channels.)
-    // /// track-list/N/demux-channels
-    // ///     Channel layout as indicated by the container. (Not always accurate.)
-    // /// track-list/N/demux-samplerate
-    // ///     Audio sample rate as indicated by the container. (Not always accurate.)
-    // /// track-list/N/demux-fps
-    // ///     Video FPS as indicated by the container. (Not always accurate.)
-    // /// track-list/N/demux-bitrate
-    // ///     Audio average bitrate, in bits per second. (Not always accurate.)
-    // /// track-list/N/demux-rotation
-    // ///     Video clockwise rotation metadata, in degrees.
-    // /// track-list/N/demux-par
-    // ///     Pixel aspect ratio.
-    // /// track-list/N/audio-channels (deprecated)
-    // ///     Deprecated alias for track-list/N/demux-channel-count.
-    // /// track-list/N/replaygain-track-peak, track-list/N/replaygain-track-gain
-    // ///     Per-track replaygain values. Only available for audio tracks with corresponding information stored in the source file.
-    // /// track-list/N/replaygain-album-peak, track-list/N/replaygain-album-gain
-    // ///     Per-album replaygain values. If the file has per-track but no per-album information, the per-album values will be copied from the per-track values currently. It's possible that future mpv versions will make these properties unavailable instead in this case.
-    //
-    // /// When querying the property with the client API using MPV_FORMAT_NODE, or with Lua mp.get_property_native, this will return a mpv_node with the following contents:
-    //
-    // /// MPV_FORMAT_NODE_ARRAY
-    // ///     MPV_FORMAT_NODE_MAP (for each track)
-    // ///         "id"                MPV_FORMAT_INT64
-    // ///         "type"              MPV_FORMAT_STRING
-    // ///         "src-id"            MPV_FORMAT_INT64
-    // ///         "title"             MPV_FORMAT_STRING
-    // ///         "lang"              MPV_FORMAT_STRING
-    // ///         "albumart"          MPV_FORMAT_FLAG
-    // ///         "default"           MPV_FORMAT_FLAG
-    // ///         "forced"            MPV_FORMAT_FLAG
-    // ///         "selected"          MPV_FORMAT_FLAG
-    // ///         "external"          MPV_FORMAT_FLAG
-    // ///         "external-filename" MPV_FORMAT_STRING
-    // ///         "codec"             MPV_FORMAT_STRING
-    // ///         "ff-index"          MPV_FORMAT_INT64
-    // ///         "decoder-desc"      MPV_FORMAT_STRING
-    // ///         "demux-w"           MPV_FORMAT_INT64
-    // ///         "demux-h"           MPV_FORMAT_INT64
-    // ///         "demux-channel-count" MPV_FORMAT_INT64
-    // ///         "demux-channels"    MPV_FORMAT_STRING
-    // ///         "demux-samplerate"  MPV_FORMAT_INT64
-    // ///         "demux-fps"         MPV_FORMAT_DOUBLE
-    // ///         "demux-bitrate"     MPV_FORMAT_INT64
-    // ///         "demux-rotation"    MPV_FORMAT_INT64
-    // ///         "demux-par"         MPV_FORMAT_DOUBLE
-    // ///         "audio-channels"    MPV_FORMAT_INT64
-    // ///         "replaygain-track-peak" MPV_FORMAT_DOUBLE
-    // ///         "replaygain-track-gain" MPV_FORMAT_DOUBLE
-    // ///         "replaygain-album-peak" MPV_FORMAT_DOUBLE
-    // ///         "replaygain-album-gain" MPV_FORMAT_DOUBLE
-    //
-    // chapter-list,
-    //
-    // /// List of chapters, current entry marked. Currently, the raw property value is useless.
-    //
-    // /// This has a number of sub-properties. Replace N with the 0-based chapter index.
-    //
-    // /// chapter-list/count
-    // ///     Number of chapters.
-    // /// chapter-list/N/title
-    // ///     Chapter title as stored in the file. Not always available.
-    // /// chapter-list/N/time
-    // ///     Chapter start time in seconds as float.
-    //
-    // /// When querying the property with the client API using MPV_FORMAT_NODE, or with Lua mp.get_property_native, this will return a mpv_node with the following contents:
-    //
-    // /// MPV_FORMAT_NODE_ARRAY
-    // ///     MPV_FORMAT_NODE_MAP (for each chapter)
-    // ///         "title" MPV_FORMAT_STRING
-    // ///         "time"  MPV_FORMAT_DOUBLE
-    //
+    /*
+
+    */
+    /// **(RW)** Current position on playlist.
+    ///
+    /// The first entry is on position 0.
+    /// Writing to this property may start playback at the new position.
+    ///
+    /// In some cases, this is not necessarily the currently playing file.
+    /// See explanation of current and playing flags in playlist.
+    ///
+    /// If there the playlist is empty, or if it's non-empty, but no entry is "current",
+    /// this property returns -1.
+    /// Likewise, writing -1 will put the player into idle mode
+    /// (or exit playback if idle mode is not enabled).
+    /// If an out of range index is written to the property, this behaves as if writing -1.
+    /// (Before mpv 0.33.0, instead of returning -1,
+    /// this property was unavailable if no playlist entry was current.)
+    ///
+    /// Writing the current value back to the property is subject to change.
+    /// Currently, it will restart playback of the playlist entry.
+    /// But in the future, writing the current value will be ignored.
+    /// Use the `playlist-play-index` command to get guaranteed behavior.
+    PlaylistPos,
+    /// **(RW)** Same as `playlist-pos`, but 1-based.
+    #[serde(rename = "playlist-pos-1")]
+    PlaylistPos1,
+    /// **(RW)** Index of the "current" item on playlist.
+    ///
+    /// This usually, but not necessarily, the currently playing item (see `playlist-playing-pos`).
+    /// Depending on the exact internal state of the player,
+    /// it may refer to the playlist item to play next,
+    /// or the playlist item used to determine what to play next.
+    ///
+    /// For reading, this is exactly the same as `playlist-pos`.
+    ///
+    /// For writing, this only sets the position of the "current" item,
+    /// without stopping playback of the current file
+    /// (or starting playback, if this is done in idle mode).
+    /// Use -1 to remove the current flag.
+    ///
+    /// This property is only vaguely useful.
+    /// If set during playback, it will typically cause the playlist entry after it to be played next.
+    /// Another possibly odd observable state is that if `playlist-next` is run during playback,
+    /// this property is set to the playlist entry to play next (unlike the previous case).
+    /// There is an internal flag that decides whether the current playlist entry
+    /// or the next one should be played, and this flag is currently inaccessible for API users.
+    /// (Whether this behavior will kept is possibly subject to change.)
+    PlaylistCurrentPos,
+    /// Index of the "playing" item on playlist.
+    ///
+    /// A playlist item is "playing" if it's being loaded, actually playing, or being unloaded.
+    /// This property is set during the `start-file` and the `end-file` events.
+    /// Outside of that, it returns -1.
+    /// If the playlist entry was somehow removed during playback,
+    /// but playback hasn't stopped yet, or is in progress of being stopped, it also returns -1.
+    /// (This can happen at least during state transitions.)
+    ///
+    /// In the "playing" state, this is usually the same as `playlist-pos`,
+    /// except during state changes, or if `playlist-current-pos` was written explicitly.
+    PlaylistPlayingPos,
+    /// Number of total playlist entries.
+    PlaylistCount,
+    /// Playlist, current entry marked.
+    ///
+    /// Can be retrieved as a list of [`PlaylistEntry`] values,
+    /// see [`MpvSocket::playlist`].
+    ///
+    /// [`PlaylistEntry`]: ./struct.PlaylistEntry.html
+    /// [`MpvSocket::playlist`]: ./struct.MpvSocket.html#method.playlist
+    Playlist,
+    /*
+
+    */
+    /*
+
+    */
+    /// List of audio/video/sub tracks, current entry marked.
+    ///
+    /// Can be retrieved as a list of [`Track`] values,
+    /// see [`MpvSocket::tracks`].
+    ///
+    /// [`Track`]: ./struct.Track.html
+    /// [`MpvSocket::tracks`]: ./struct.MpvSocket.html#method.tracks
+    TrackList,
+    /// List of chapters, current entry marked.
+    ///
+    /// Can be retrieved as a list of [`Chapter`] values,
+    /// see [`MpvSocket::chapters`].
+    ///
+    /// [`Chapter`]: ./struct.Chapter.html
+    /// [`MpvSocket::chapters`]: ./struct.MpvSocket.html#method.chapters
+    ChapterList,
+    /*
+
+    */
     // af, vf (RW),
     //
     // /// See --vf/--af and the vf/af command.
@@ -985,11 +925,33 @@ pub enum Property {
     Volume,
     /// Pause or unpause.
     Pause,
+    /// **(RW)** Select audio track.
+    ///
+    /// `auto` selects the default, `no` disables audio.
+    /// See also [`MpvSocket::set_audio_track`].
+    ///
+    /// [`MpvSocket::set_audio_track`]: ./struct.MpvSocket.html#method.set_audio_track
+    Aid,
+    /// **(RW)** Select subtitle track.
+    ///
+    /// `auto` selects the default, `no` disables subtitles.
+    /// See also [`MpvSocket::set_sub_track`].
+    ///
+    /// [`MpvSocket::set_sub_track`]: ./struct.MpvSocket.html#method.set_sub_track
+    Sid,
+    /// **(RW)** Select video track.
+    ///
+    /// `auto` selects the default, `no` disables video.
+    /// See also [`MpvSocket::set_video_track`].
+    ///
+    /// [`MpvSocket::set_video_track`]: ./struct.MpvSocket.html#method.set_video_track
+    Vid,
 }
 
-impl<'a> From<&'a Property> for Value {
-    fn from(property: &'a Property) -> Self {
-        let value = match property {
+impl Property {
+    /// Returns the name of the property as used by mpv.
+    pub fn as_str(self) -> &'static str {
+        match self {
             Property::AudioSpeedCorrection => "audio-speed-correction",
             Property::VideoSpeedCorrection => "video-speed-correction",
             Property::DisplaySyncActive => "display-sync-active",
@@ -1012,19 +974,174 @@ impl<'a> From<&'a Property> for Value {
             Property::TimeStart => "time-start",
             Property::TimeRemaining => "time-remaining",
             Property::PlaybackTime => "playback-time",
+            Property::Chapter => "chapter",
+            Property::Edition => "edition",
+            Property::CurrentEdition => "current-edition",
+            Property::Chapters => "chapters",
+            Property::Editions => "editions",
+            Property::EditionList => "edition-list",
+            Property::ChapterMetadata => "chapter-metadata",
             Property::Seeking => "seeking",
+            Property::PlaylistPos => "playlist-pos",
+            Property::PlaylistPos1 => "playlist-pos-1",
+            Property::PlaylistCurrentPos => "playlist-current-pos",
+            Property::PlaylistPlayingPos => "playlist-playing-pos",
+            Property::PlaylistCount => "playlist-count",
+            Property::Playlist => "playlist",
+            Property::TrackList => "track-list",
+            Property::ChapterList => "chapter-list",
             // Where are these documented?
             Property::Volume => "volume",
             Property::Pause => "pause",
-        };
-        Value::from(value)
+            Property::Aid => "aid",
+            Property::Sid => "sid",
+            Property::Vid => "vid",
+        }
+    }
+
+    /// Returns the path of a sub-property of this property,
+    /// like `track-list/2/lang` for `Property::TrackList.sub_property("2/lang")`.
+    pub fn sub_property(self, path: &str) -> PropertyName {
+        PropertyName::from(format!("{}/{}", self.as_str(), path))
+    }
+}
+
+impl<'a> From<&'a Property> for Value {
+    fn from(property: &'a Property) -> Self {
+        Value::from(property.as_str())
     }
 }
 
 impl fmt::Display for Property {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let value: Value = self.into();
-        write!(f, "{}", value.as_str().unwrap())
+        f.write_str(self.as_str())
+    }
+}
+
+/// Name of a property.
+///
+/// This is either one of the known [`Property`] variants
+/// or an arbitrary property path like `metadata/by-key/Artist`, `options/ytdl-format`
+/// or `track-list/2/lang`, which can't be expressed as a [`Property`].
+///
+/// Property names are normalized when converted from strings,
+/// so `PropertyName::from("volume")` is [`PropertyName::Known`]`(`[`Property::Volume`]`)`.
+///
+/// [`Property`]: ./enum.Property.html
+/// [`Property::Volume`]: ./enum.Property.html#variant.Volume
+/// [`PropertyName::Known`]: #variant.Known
+#[derive(Debug, Clone)]
+pub enum PropertyName {
+    /// A property known to this library.
+    Known(Property),
+    /// Any other property or sub-property path.
+    Custom(String),
+}
+
+impl PropertyName {
+    /// Returns the name of the property as used by mpv.
+    pub fn as_str(&self) -> &str {
+        match self {
+            PropertyName::Known(property) => property.as_str(),
+            PropertyName::Custom(name) => name,
+        }
+    }
+
+    /// The value of the option with the given name, like `options/ytdl-format`.
+    ///
+    /// Writing to it works like setting the option at runtime.
+    pub fn option(name: &str) -> PropertyName {
+        PropertyName::Custom(format!("options/{}", name))
+    }
+
+    /// The value of the option with the given name,
+    /// but setting it will be reset when playback of the current file ends.
+    pub fn file_local_option(name: &str) -> PropertyName {
+        PropertyName::Custom(format!("file-local-options/{}", name))
+    }
+
+    /// Additional per-option information of the option with the given name.
+    pub fn option_info(name: &str) -> PropertyName {
+        PropertyName::Custom(format!("option-info/{}", name))
+    }
+
+    /// The value of the metadata entry with the given key, like `metadata/by-key/Artist`.
+    pub fn metadata_by_key(key: &str) -> PropertyName {
+        PropertyName::Custom(format!("metadata/by-key/{}", key))
+    }
+
+    /// Metadata added by the video filter with the given label.
+    pub fn vf_metadata(label: &str) -> PropertyName {
+        PropertyName::Custom(format!("vf-metadata/{}", label))
+    }
+
+    /// Metadata added by the audio filter with the given label.
+    pub fn af_metadata(label: &str) -> PropertyName {
+        PropertyName::Custom(format!("af-metadata/{}", label))
+    }
+}
+
+impl From<Property> for PropertyName {
+    fn from(property: Property) -> Self {
+        PropertyName::Known(property)
+    }
+}
+
+impl From<String> for PropertyName {
+    fn from(name: String) -> Self {
+        let deserializer: serde::de::value::StrDeserializer<'_, serde::de::value::Error> =
+            name.as_str().into_deserializer();
+        match Property::deserialize(deserializer) {
+            Ok(property) => PropertyName::Known(property),
+            Err(_) => PropertyName::Custom(name),
+        }
+    }
+}
+
+impl<'a> From<&'a str> for PropertyName {
+    fn from(name: &'a str) -> Self {
+        PropertyName::from(String::from(name))
+    }
+}
+
+impl<'a> From<&'a PropertyName> for Value {
+    fn from(property: &'a PropertyName) -> Self {
+        Value::from(property.as_str())
+    }
+}
+
+impl PartialEq for PropertyName {
+    fn eq(&self, other: &PropertyName) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+impl Eq for PropertyName {}
+
+impl PartialEq<Property> for PropertyName {
+    fn eq(&self, other: &Property) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+impl Hash for PropertyName {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_str().hash(state)
+    }
+}
+
+impl fmt::Display for PropertyName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for PropertyName {
+    fn deserialize<D>(deserializer: D) -> std::result::Result<PropertyName, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        String::deserialize(deserializer).map(PropertyName::from)
     }
 }
 
@@ -1042,7 +1159,7 @@ impl TryFromValue for bool {
     fn try_from(value: Value) -> Result<bool> {
         match value {
             Value::Bool(value) => Ok(value),
-            _ => Err(format!("expected bool, but got: {:?}", value).into()),
+            _ => Err(Error::type_mismatch("bool", value)),
         }
     }
 }
@@ -1051,7 +1168,7 @@ impl TryFromValue for u64 {
     fn try_from(value: Value) -> Result<u64> {
         value
             .as_u64()
-            .ok_or_else(|| format!("expected u64, but got: {:?}", value).into())
+            .ok_or_else(|| Error::type_mismatch("u64", value))
     }
 }
 
@@ -1059,7 +1176,7 @@ impl TryFromValue for i64 {
     fn try_from(value: Value) -> Result<i64> {
         value
             .as_i64()
-            .ok_or_else(|| format!("expected i64, but got: {:?}", value).into())
+            .ok_or_else(|| Error::type_mismatch("i64", value))
     }
 }
 
@@ -1067,7 +1184,7 @@ impl TryFromValue for f64 {
     fn try_from(value: Value) -> Result<f64> {
         value
             .as_f64()
-            .ok_or_else(|| format!("expected f64, but got: {:?}", value).into())
+            .ok_or_else(|| Error::type_mismatch("f64", value))
     }
 }
 
@@ -1075,25 +1192,36 @@ impl TryFromValue for String {
     fn try_from(value: Value) -> Result<String> {
         match value {
             Value::String(value) => Ok(value),
-            _ => Err(format!("expected string, but got: {:?}", value).into()),
+            _ => Err(Error::type_mismatch("string", value)),
         }
     }
 }
 
-impl TryFromValue for Vec<Value> {
-    fn try_from(value: Value) -> Result<Vec<Value>> {
+impl<T> TryFromValue for Vec<T>
+where
+    T: TryFromValue,
+{
+    fn try_from(value: Value) -> Result<Vec<T>> {
         match value {
-            Value::Array(value) => Ok(value),
-            _ => Err(format!("expected array, but got: {:?}", value).into()),
+            Value::Array(value) => value.into_iter().map(T::try_from).collect(),
+            _ => Err(Error::type_mismatch("array", value)),
         }
     }
 }
 
+/// Convert a value by deserializing it, like the entries of list properties.
+pub(crate) fn deserialize_value<T>(value: Value) -> Result<T>
+where
+    T: serde::de::DeserializeOwned,
+{
+    Ok(serde_json::from_value(value)?)
+}
+
 impl TryFromValue for Map<String, Value> {
     fn try_from(value: Value) -> Result<Map<String, Value>> {
         match value {
             Value::Object(value) => Ok(value),
-            _ => Err(format!("expected object, but got: {:?}", value).into()),
+            _ => Err(Error::type_mismatch("object", value)),
         }
     }
 }
@@ -1,7 +1,10 @@
 use serde::{Deserialize, Serialize};
 
-use crate::event::Event;
-use crate::{Property, Value};
+use crate::command::format_options;
+use crate::event::{Event, EventKind, MpvLogLevel};
+use crate::{
+    Hook, LoadFileMode, NamedCommand, Property, PropertyName, SeekMode, SeekPrecision, Value,
+};
 
 #[derive(Serialize)]
 pub(crate) struct Request {
@@ -9,6 +12,14 @@ pub(crate) struct Request {
     pub request_id: i64,
 }
 
+#[derive(Serialize)]
+pub(crate) struct AsyncRequest {
+    #[serde(flatten)]
+    pub request: Request,
+    #[serde(rename = "async")]
+    pub is_async: bool,
+}
+
 #[derive(Debug, PartialEq, Deserialize)]
 pub(crate) struct CommandResponse {
     pub request_id: Option<i64>,
@@ -18,28 +29,72 @@ pub(crate) struct CommandResponse {
 }
 
 #[derive(Debug, Deserialize, PartialEq)]
+#[serde(from = "RawEventResponse")]
 pub(crate) struct EventResponse {
-    #[serde(flatten)]
     pub event: Event,
     pub id: Option<i64>,
     pub error: Option<String>,
 }
 
+#[derive(Deserialize)]
+struct RawEventResponse {
+    #[serde(flatten)]
+    event: Event,
+    id: Option<i64>,
+    error: Option<String>,
+}
+
+impl From<RawEventResponse> for EventResponse {
+    fn from(raw: RawEventResponse) -> Self {
+        let mut event = raw.event;
+        // The id is shared with the response, so it is not part of the flattened event.
+        if let (Event::Hook(hook_event), Some(id)) = (&mut event, raw.id) {
+            hook_event.id = id;
+        }
+        EventResponse {
+            event,
+            id: raw.id,
+            error: raw.error,
+        }
+    }
+}
+
 pub(crate) enum Command {
     ClientName,
     GetTimeUs,
-    GetProperty(Property),
-    SetProperty(Property, Value),
-    ObserveProperty(i64, Property),
+    GetProperty(PropertyName),
+    SetProperty(PropertyName, Value),
+    ObserveProperty(i64, PropertyName),
     UnobserveProperty(i64),
-    // RequestLogMessages,
-    // EnableEvent(EventType),
-    // DisableEvent(EventType),
+    RequestLogMessages(MpvLogLevel),
+    EnableEvent(EventKind),
+    DisableEvent(EventKind),
     GetVersion,
+    LoadFile(String, LoadFileMode, Vec<(String, String)>),
+    LoadList(String, LoadFileMode),
+    Seek(f64, SeekMode, SeekPrecision),
+    RevertSeek,
+    FrameStep,
+    FrameBackStep,
+    PlaylistNext,
+    PlaylistPrev,
+    PlaylistClear,
+    PlaylistRemove(i64),
+    PlaylistMove(i64, i64),
+    PlaylistShuffle,
+    PlaylistUnshuffle,
+    Add(Property, Value),
+    HookAdd(Hook, i64, i64),
+    HookAck(u64),
+    ScriptMessage(Vec<String>),
+    ScriptMessageTo(String, Vec<String>),
+    AbortAsync(i64),
+    Named(NamedCommand),
+    Raw(String, Vec<Value>),
 }
 
 impl Command {
-    pub fn name(&self) -> &'static str {
+    pub fn name(&self) -> &str {
         match self {
             Command::ClientName => "client_name",
             Command::GetTimeUs => "get_time_us",
@@ -47,10 +102,31 @@ impl Command {
             Command::SetProperty(..) => "set_property",
             Command::ObserveProperty(..) => "observe_property",
             Command::UnobserveProperty(..) => "unobserve_property",
-            // Command::RequestLogMessages => "request_log_messages",
-            // Command::EnableEvent(..) => "enable_event",
-            // Command::DisableEvent(..) => "disable_event",
+            Command::RequestLogMessages(..) => "request_log_messages",
+            Command::EnableEvent(..) => "enable_event",
+            Command::DisableEvent(..) => "disable_event",
             Command::GetVersion => "get_version",
+            Command::LoadFile(..) => "loadfile",
+            Command::LoadList(..) => "loadlist",
+            Command::Seek(..) => "seek",
+            Command::RevertSeek => "revert-seek",
+            Command::FrameStep => "frame-step",
+            Command::FrameBackStep => "frame-back-step",
+            Command::PlaylistNext => "playlist-next",
+            Command::PlaylistPrev => "playlist-prev",
+            Command::PlaylistClear => "playlist-clear",
+            Command::PlaylistRemove(..) => "playlist-remove",
+            Command::PlaylistMove(..) => "playlist-move",
+            Command::PlaylistShuffle => "playlist-shuffle",
+            Command::PlaylistUnshuffle => "playlist-unshuffle",
+            Command::Add(..) => "add",
+            Command::HookAdd(..) => "hook-add",
+            Command::HookAck(..) => "hook-ack",
+            Command::ScriptMessage(..) => "script-message",
+            Command::ScriptMessageTo(..) => "script-message-to",
+            Command::AbortAsync(..) => "abort-async-command",
+            Command::Named(command) => &command.name,
+            Command::Raw(name, _) => name,
         }
     }
 
@@ -62,10 +138,58 @@ impl Command {
             Command::SetProperty(property, value) => vec![property.into(), value.clone()],
             Command::ObserveProperty(id, property) => vec![(*id).into(), property.into()],
             Command::UnobserveProperty(id) => vec![(*id).into()],
-            // Command::RequestLogMessages => vec![],
-            // Command::EnableEvent(event) => vec![event.into()],
-            // Command::DisableEvent(event) => vec![event.into()],
+            Command::RequestLogMessages(level) => vec![(*level).into()],
+            Command::EnableEvent(event) => vec![(*event).into()],
+            Command::DisableEvent(event) => vec![(*event).into()],
             Command::GetVersion => vec![],
+            Command::LoadFile(url, mode, options) => {
+                let mut params = vec![url.as_str().into(), mode.flag().into()];
+                if mode.index().is_some() || !options.is_empty() {
+                    params.push(mode.index().unwrap_or(-1).into());
+                }
+                if !options.is_empty() {
+                    params.push(format_options(options));
+                }
+                params
+            }
+            Command::LoadList(url, mode) => {
+                let mut params = vec![url.as_str().into(), mode.flag().into()];
+                if let Some(index) = mode.index() {
+                    params.push(index.into());
+                }
+                params
+            }
+            Command::Seek(target, mode, precision) => {
+                let flags = match precision.flag() {
+                    Some(precision) => format!("{}+{}", mode.flag(), precision),
+                    None => String::from(mode.flag()),
+                };
+                vec![(*target).into(), flags.into()]
+            }
+            Command::RevertSeek => vec![],
+            Command::FrameStep => vec![],
+            Command::FrameBackStep => vec![],
+            Command::PlaylistNext => vec![],
+            Command::PlaylistPrev => vec![],
+            Command::PlaylistClear => vec![],
+            Command::PlaylistRemove(index) => vec![(*index).into()],
+            Command::PlaylistMove(index1, index2) => vec![(*index1).into(), (*index2).into()],
+            Command::PlaylistShuffle => vec![],
+            Command::PlaylistUnshuffle => vec![],
+            Command::Add(property, value) => vec![property.into(), value.clone()],
+            Command::HookAdd(hook, id, priority) => {
+                vec![(*hook).into(), (*id).into(), (*priority).into()]
+            }
+            Command::HookAck(hook_id) => vec![(*hook_id).into()],
+            Command::ScriptMessage(args) => args.iter().map(|arg| arg.as_str().into()).collect(),
+            Command::ScriptMessageTo(target, args) => std::iter::once(target)
+                .chain(args)
+                .map(|arg| arg.as_str().into())
+                .collect(),
+            Command::AbortAsync(id) => vec![(*id).into()],
+            // serialized as an object, see `serde_impl`
+            Command::Named(..) => vec![],
+            Command::Raw(_, args) => args.clone(),
         }
     }
 }
@@ -0,0 +1,194 @@
+//! Routing of replies and events for the clients with a background reader.
+//!
+//! Shared by [`MpvClient`] and the async client,
+//! which only differ in how they read, write and wait.
+//!
+//! [`MpvClient`]: ../struct.MpvClient.html
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicI64, Ordering};
+use std::sync::{mpsc, Mutex};
+
+use crate::event::{Event, PropertyChangeEvent};
+use crate::protocol::{CommandResponse, EventResponse};
+use crate::{reply_data, Error, Result, Value};
+
+/// Sending half of the channel a reply is waited on.
+pub(crate) trait ReplySender {
+    fn send_reply(self, result: Result<Value>);
+}
+
+/// Sending half of the channel a subscriber receives events on.
+pub(crate) trait EventSender {
+    /// Returns `false` if the subscriber is gone.
+    fn send_event(&self, event: Event) -> bool;
+}
+
+impl ReplySender for mpsc::Sender<Result<Value>> {
+    fn send_reply(self, result: Result<Value>) {
+        let _ = self.send(result);
+    }
+}
+
+impl EventSender for mpsc::Sender<Event> {
+    fn send_event(&self, event: Event) -> bool {
+        self.send(event).is_ok()
+    }
+}
+
+struct Subscriber<E> {
+    observe_id: Option<i64>,
+    sender: E,
+}
+
+/// Passes replies to the requests waiting for them by request id,
+/// and events to the subscribers.
+pub(crate) struct Router<R, E> {
+    last_request_id: AtomicI64,
+    last_observe_id: AtomicI64,
+    pending_replies: Mutex<HashMap<i64, R>>,
+    subscribers: Mutex<Vec<Subscriber<E>>>,
+    closed: AtomicBool,
+}
+
+impl<R: ReplySender, E: EventSender> Router<R, E> {
+    pub(crate) fn new() -> Router<R, E> {
+        Router {
+            last_request_id: AtomicI64::new(0),
+            last_observe_id: AtomicI64::new(0),
+            pending_replies: Mutex::new(HashMap::new()),
+            subscribers: Mutex::new(Vec::new()),
+            closed: AtomicBool::new(false),
+        }
+    }
+
+    pub(crate) fn next_request_id(&self) -> i64 {
+        self.last_request_id.fetch_add(1, Ordering::Relaxed) + 1
+    }
+
+    pub(crate) fn next_observe_id(&self) -> i64 {
+        self.last_observe_id.fetch_add(1, Ordering::Relaxed) + 1
+    }
+
+    pub(crate) fn is_closed(&self) -> bool {
+        self.closed.load(Ordering::SeqCst)
+    }
+
+    /// Register the sender the reply to the given request is passed to.
+    ///
+    /// The request is forgotten when the returned guard is dropped,
+    /// so requests which are given up on, time out or are cancelled don't leak.
+    pub(crate) fn add_pending_reply(
+        &self,
+        request_id: i64,
+        sender: R,
+    ) -> Result<PendingReply<'_, R, E>> {
+        let mut pending_replies = self.pending_replies.lock().unwrap();
+        if self.is_closed() {
+            return Err(Error::Closed);
+        }
+        pending_replies.insert(request_id, sender);
+        Ok(PendingReply {
+            router: self,
+            request_id,
+        })
+    }
+
+    #[cfg(all(test, feature = "tokio"))]
+    pub(crate) fn num_pending_replies(&self) -> usize {
+        self.pending_replies.lock().unwrap().len()
+    }
+
+    /// Pass events to the given sender,
+    /// only property changes of the given observation if there is one.
+    pub(crate) fn subscribe(&self, observe_id: Option<i64>, sender: E) -> Result<()> {
+        let mut subscribers = self.subscribers.lock().unwrap();
+        if self.is_closed() {
+            return Err(Error::Closed);
+        }
+        subscribers.push(Subscriber { observe_id, sender });
+        Ok(())
+    }
+
+    pub(crate) fn unsubscribe(&self, observe_id: i64) {
+        let mut subscribers = self.subscribers.lock().unwrap();
+        subscribers.retain(|subscriber| subscriber.observe_id != Some(observe_id));
+    }
+
+    /// Route a line received from mpv.
+    pub(crate) fn dispatch(&self, line: &[u8]) {
+        let res_json = String::from_utf8_lossy(line);
+        if log::log_enabled!(log::Level::Trace) {
+            log::trace!("received: {}", res_json.trim());
+        }
+
+        let response: CommandResponse = match serde_json::from_str(res_json.as_ref()) {
+            Ok(response) => response,
+            Err(error) => {
+                log::error!("invalid message from mpv: {}", error);
+                return;
+            }
+        };
+
+        if let Some(request_id) = response.request_id {
+            let sender = self.pending_replies.lock().unwrap().remove(&request_id);
+            match sender {
+                Some(sender) => sender.send_reply(reply_data(response)),
+                None => log::debug!("ignoring reply to abandoned request: {}", request_id),
+            }
+            return;
+        }
+
+        let res_event: EventResponse = match serde_json::from_str(res_json.as_ref()) {
+            Ok(event) => event,
+            Err(error) => {
+                log::error!("invalid event from mpv: {}", error);
+                return;
+            }
+        };
+
+        let mut subscribers = self.subscribers.lock().unwrap();
+        subscribers.retain(|subscriber| match subscriber.observe_id {
+            Some(observe_id) if res_event.id != Some(observe_id) => true,
+            _ => subscriber.sender.send_event(res_event.event.clone()),
+        });
+    }
+
+    /// Mark the connection as closed and fail all waiting requests.
+    pub(crate) fn close(&self) {
+        {
+            let mut pending_replies = self.pending_replies.lock().unwrap();
+            self.closed.store(true, Ordering::SeqCst);
+            pending_replies.clear();
+        }
+        self.subscribers.lock().unwrap().clear();
+    }
+}
+
+/// Guard of a request waiting for its reply.
+///
+/// Returned by [`Router::add_pending_reply`],
+/// a reply arriving after the guard was dropped is ignored.
+pub(crate) struct PendingReply<'a, R: ReplySender, E: EventSender> {
+    router: &'a Router<R, E>,
+    request_id: i64,
+}
+
+impl<R: ReplySender, E: EventSender> Drop for PendingReply<'_, R, E> {
+    fn drop(&mut self) {
+        let mut pending_replies = self.router.pending_replies.lock().unwrap();
+        pending_replies.remove(&self.request_id);
+    }
+}
+
+/// The new value of an observed property,
+/// or `None` for other events and unavailable properties, which are reported without data.
+pub(crate) fn property_change_data(event: Event) -> Option<Value> {
+    match event {
+        Event::PropertyChange(PropertyChangeEvent {
+            data: Value::Null, ..
+        }) => None,
+        Event::PropertyChange(PropertyChangeEvent { data, .. }) => Some(data),
+        _ => None,
+    }
+}
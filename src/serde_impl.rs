@@ -1,13 +1,17 @@
-use serde::ser::SerializeSeq;
+use serde::ser::{SerializeMap, SerializeSeq};
 use serde::{Serialize, Serializer};
 
-use crate::Command;
+use crate::{Command, NamedCommand};
 
 impl Serialize for Command {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: Serializer,
     {
+        if let Command::Named(command) = self {
+            return command.serialize(serializer);
+        }
+
         let name = self.name();
         let params = self.params();
 
@@ -20,12 +24,32 @@ impl Serialize for Command {
     }
 }
 
+impl Serialize for NamedCommand {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut map = serializer.serialize_map(Some(1 + self.args.len()))?;
+        map.serialize_entry("name", &self.name)?;
+        for (name, value) in &self.args {
+            map.serialize_entry(name, value)?;
+        }
+        map.end()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::event::{Event, PropertyChangeEvent};
-    use crate::protocol::EventResponse;
-    use crate::{CommandResponse, Property, Request, Value};
+    use crate::event::{
+        ClientMessageEvent, EndFileEvent, Event, EventKind, HookEvent, LogMessageEvent,
+        MpvLogLevel, PropertyChangeEvent, Reason,
+    };
+    use crate::protocol::{AsyncRequest, EventResponse};
+    use crate::{
+        Chapter, CommandResponse, Hook, LoadFileMode, PlaylistEntry, Property, PropertyName,
+        Request, SeekMode, SeekPrecision, Track, TrackSelection, TrackType, TryFromValue, Value,
+    };
 
     #[test]
     fn serialize_request_client_name() {
@@ -40,7 +64,7 @@ mod tests {
     #[test]
     fn serialize_request_get_property_volume() {
         let request = Request {
-            command: Command::GetProperty(Property::Volume),
+            command: Command::GetProperty(Property::Volume.into()),
             request_id: 1,
         };
         let json = serde_json::to_string(&request).unwrap();
@@ -53,7 +77,7 @@ mod tests {
     #[test]
     fn serialize_request_set_property_pause() {
         let request = Request {
-            command: Command::SetProperty(Property::Pause, Value::from(true)),
+            command: Command::SetProperty(Property::Pause.into(), Value::from(true)),
             request_id: 1,
         };
         let json = serde_json::to_string(&request).unwrap();
@@ -63,10 +87,52 @@ mod tests {
         );
     }
 
+    #[test]
+    fn serialize_request_get_property_custom() {
+        let request = Request {
+            command: Command::GetProperty(PropertyName::metadata_by_key("Artist")),
+            request_id: 1,
+        };
+        let json = serde_json::to_string(&request).unwrap();
+        assert_eq!(
+            json,
+            r#"{"command":["get_property","metadata/by-key/Artist"],"request_id":1}"#
+        );
+    }
+
+    #[test]
+    fn serialize_request_get_property_sub_property() {
+        let request = Request {
+            command: Command::GetProperty(Property::TrackList.sub_property("2/lang")),
+            request_id: 1,
+        };
+        let json = serde_json::to_string(&request).unwrap();
+        assert_eq!(
+            json,
+            r#"{"command":["get_property","track-list/2/lang"],"request_id":1}"#
+        );
+    }
+
+    #[test]
+    fn property_name_from_str_is_normalized() {
+        assert_eq!(
+            PropertyName::from("filename/no-ext"),
+            PropertyName::Known(Property::FilenameNoExt)
+        );
+        assert_eq!(
+            PropertyName::from("playlist-pos-1"),
+            PropertyName::Known(Property::PlaylistPos1)
+        );
+        assert_eq!(
+            PropertyName::from("options/ytdl-format"),
+            PropertyName::Custom(String::from("options/ytdl-format"))
+        );
+    }
+
     #[test]
     fn serialize_request_observe_property_volume() {
         let request = Request {
-            command: Command::ObserveProperty(1, Property::Volume),
+            command: Command::ObserveProperty(1, Property::Volume.into()),
             request_id: 1,
         };
         let json = serde_json::to_string(&request).unwrap();
@@ -76,6 +142,237 @@ mod tests {
         );
     }
 
+    #[test]
+    fn serialize_request_raw_command() {
+        let request = Request {
+            command: Command::Raw(
+                String::from("loadfile"),
+                vec![Value::from("video.mkv"), Value::from("append")],
+            ),
+            request_id: 1,
+        };
+        let json = serde_json::to_string(&request).unwrap();
+        assert_eq!(
+            json,
+            r#"{"command":["loadfile","video.mkv","append"],"request_id":1}"#
+        );
+    }
+
+    #[test]
+    fn serialize_request_loadfile_append() {
+        let request = Request {
+            command: Command::LoadFile(String::from("video.mkv"), LoadFileMode::Append, vec![]),
+            request_id: 1,
+        };
+        let json = serde_json::to_string(&request).unwrap();
+        assert_eq!(
+            json,
+            r#"{"command":["loadfile","video.mkv","append"],"request_id":1}"#
+        );
+    }
+
+    #[test]
+    fn serialize_request_loadfile_insert_at_with_options() {
+        let request = Request {
+            command: Command::LoadFile(
+                String::from("video.mkv"),
+                LoadFileMode::InsertAt(2),
+                vec![
+                    (String::from("start"), String::from("30")),
+                    (String::from("title"), String::from("a,b")),
+                ],
+            ),
+            request_id: 1,
+        };
+        let json = serde_json::to_string(&request).unwrap();
+        assert_eq!(
+            json,
+            r#"{"command":["loadfile","video.mkv","insert-at",2,"start=30,title=%3%a,b"],"request_id":1}"#
+        );
+    }
+
+    #[test]
+    fn serialize_request_loadlist_replace() {
+        let request = Request {
+            command: Command::LoadList(String::from("list.m3u"), LoadFileMode::Replace),
+            request_id: 1,
+        };
+        let json = serde_json::to_string(&request).unwrap();
+        assert_eq!(
+            json,
+            r#"{"command":["loadlist","list.m3u","replace"],"request_id":1}"#
+        );
+    }
+
+    #[test]
+    fn serialize_request_seek_relative() {
+        let request = Request {
+            command: Command::Seek(-5.0, SeekMode::Relative, SeekPrecision::Default),
+            request_id: 1,
+        };
+        let json = serde_json::to_string(&request).unwrap();
+        assert_eq!(
+            json,
+            r#"{"command":["seek",-5.0,"relative"],"request_id":1}"#
+        );
+    }
+
+    #[test]
+    fn serialize_request_seek_absolute_percent_exact() {
+        let request = Request {
+            command: Command::Seek(50.0, SeekMode::AbsolutePercent, SeekPrecision::Exact),
+            request_id: 1,
+        };
+        let json = serde_json::to_string(&request).unwrap();
+        assert_eq!(
+            json,
+            r#"{"command":["seek",50.0,"absolute-percent+exact"],"request_id":1}"#
+        );
+    }
+
+    #[test]
+    fn serialize_request_disable_event_audio_reconfig() {
+        let request = Request {
+            command: Command::DisableEvent(EventKind::AudioReconfig),
+            request_id: 1,
+        };
+        let json = serde_json::to_string(&request).unwrap();
+        assert_eq!(
+            json,
+            r#"{"command":["disable_event","audio-reconfig"],"request_id":1}"#
+        );
+    }
+
+    #[test]
+    fn serialize_request_enable_event_all() {
+        let request = Request {
+            command: Command::EnableEvent(EventKind::All),
+            request_id: 1,
+        };
+        let json = serde_json::to_string(&request).unwrap();
+        assert_eq!(json, r#"{"command":["enable_event","all"],"request_id":1}"#);
+    }
+
+    #[test]
+    fn serialize_request_request_log_messages() {
+        let request = Request {
+            command: Command::RequestLogMessages(MpvLogLevel::Warn),
+            request_id: 1,
+        };
+        let json = serde_json::to_string(&request).unwrap();
+        assert_eq!(
+            json,
+            r#"{"command":["request_log_messages","warn"],"request_id":1}"#
+        );
+    }
+
+    #[test]
+    fn serialize_request_hook_add() {
+        let request = Request {
+            command: Command::HookAdd(Hook::OnLoad, 1, 50),
+            request_id: 1,
+        };
+        let json = serde_json::to_string(&request).unwrap();
+        assert_eq!(
+            json,
+            r#"{"command":["hook-add","on_load",1,50],"request_id":1}"#
+        );
+    }
+
+    #[test]
+    fn serialize_request_script_message_to() {
+        let request = Request {
+            command: Command::ScriptMessageTo(
+                String::from("my_script"),
+                vec![String::from("toggle-sidebar"), String::from("1")],
+            ),
+            request_id: 1,
+        };
+        let json = serde_json::to_string(&request).unwrap();
+        assert_eq!(
+            json,
+            r#"{"command":["script-message-to","my_script","toggle-sidebar","1"],"request_id":1}"#
+        );
+    }
+
+    #[test]
+    fn serialize_async_request() {
+        let request = AsyncRequest {
+            request: Request {
+                command: Command::Raw(
+                    String::from("screenshot-to-file"),
+                    vec![Value::from("shot.png")],
+                ),
+                request_id: 1,
+            },
+            is_async: true,
+        };
+        let json = serde_json::to_string(&request).unwrap();
+        assert_eq!(
+            json,
+            r#"{"command":["screenshot-to-file","shot.png"],"request_id":1,"async":true}"#
+        );
+    }
+
+    #[test]
+    fn serialize_request_abort_async_command() {
+        let request = Request {
+            command: Command::AbortAsync(3),
+            request_id: 1,
+        };
+        let json = serde_json::to_string(&request).unwrap();
+        assert_eq!(
+            json,
+            r#"{"command":["abort-async-command",3],"request_id":1}"#
+        );
+    }
+
+    #[test]
+    fn serialize_request_named_loadfile() {
+        let request = Request {
+            command: Command::Named(
+                NamedCommand::new("loadfile")
+                    .arg("url", "video.mkv")
+                    .arg("flags", "append")
+                    .arg("options", "start=30"),
+            ),
+            request_id: 1,
+        };
+        let json = serde_json::to_string(&request).unwrap();
+        assert_eq!(
+            json,
+            r#"{"command":{"name":"loadfile","url":"video.mkv","flags":"append","options":"start=30"},"request_id":1}"#
+        );
+    }
+
+    #[test]
+    fn serialize_request_named_arg_replaced() {
+        let request = Request {
+            command: Command::Named(
+                NamedCommand::new("screenshot-to-file")
+                    .arg("filename", "a.png")
+                    .arg("flags", "video")
+                    .arg("filename", "b.png"),
+            ),
+            request_id: 1,
+        };
+        let json = serde_json::to_string(&request).unwrap();
+        assert_eq!(
+            json,
+            r#"{"command":{"name":"screenshot-to-file","filename":"b.png","flags":"video"},"request_id":1}"#
+        );
+    }
+
+    #[test]
+    fn serialize_request_named_reserved_name_arg() {
+        let request = Request {
+            command: Command::Named(NamedCommand::new("sub-add").arg("name", "quit")),
+            request_id: 1,
+        };
+        let json = serde_json::to_string(&request).unwrap();
+        assert_eq!(json, r#"{"command":{"name":"sub-add"},"request_id":1}"#);
+    }
+
     #[test]
     fn deserialize_response() {
         let input = r#"{ "error": "success" }"#;
@@ -160,6 +457,237 @@ mod tests {
         )
     }
 
+    #[test]
+    fn deserialize_response_with_data_playlist() {
+        let input = r#"{ "data": [{ "filename": "a.mkv", "current": true, "playing": true, "id": 1 }, { "filename": "b.mkv", "title": "B", "id": 2 }], "error": "success" }"#;
+        let response: CommandResponse = serde_json::from_str(input).unwrap();
+        let playlist: Vec<PlaylistEntry> = TryFromValue::try_from(response.data).unwrap();
+        assert_eq!(
+            playlist,
+            vec![
+                PlaylistEntry {
+                    filename: String::from("a.mkv"),
+                    title: None,
+                    id: Some(1),
+                    current: true,
+                    playing: true,
+                },
+                PlaylistEntry {
+                    filename: String::from("b.mkv"),
+                    title: Some(String::from("B")),
+                    id: Some(2),
+                    current: false,
+                    playing: false,
+                },
+            ]
+        )
+    }
+
+    #[test]
+    fn deserialize_response_with_data_track_list() {
+        let input = r#"{ "data": [{ "id": 1, "type": "sub", "src-id": 3, "lang": "eng", "default": true, "forced": false, "external": false, "selected": true, "codec": "subrip", "ff-index": 2 }], "error": "success" }"#;
+        let response: CommandResponse = serde_json::from_str(input).unwrap();
+        let tracks: Vec<Track> = TryFromValue::try_from(response.data).unwrap();
+        assert_eq!(tracks.len(), 1);
+        assert_eq!(tracks[0].id, 1);
+        assert_eq!(tracks[0].track_type, TrackType::Sub);
+        assert_eq!(tracks[0].src_id, Some(3));
+        assert_eq!(tracks[0].lang.as_deref(), Some("eng"));
+        assert!(tracks[0].default);
+        assert!(tracks[0].selected);
+        assert_eq!(tracks[0].codec.as_deref(), Some("subrip"));
+        assert_eq!(tracks[0].ff_index, Some(2));
+        assert_eq!(tracks[0].demux_w, None);
+    }
+
+    #[test]
+    fn deserialize_response_with_data_track_list_with_unknown_type() {
+        let input = r#"{ "data": [{ "id": 1, "type": "some-future-type", "selected": false }, { "id": 1, "type": "audio", "selected": true }], "error": "success" }"#;
+        let response: CommandResponse = serde_json::from_str(input).unwrap();
+        let tracks: Vec<Track> = TryFromValue::try_from(response.data).unwrap();
+        assert_eq!(tracks.len(), 2);
+        assert_eq!(tracks[0].track_type, TrackType::Unknown);
+        assert_eq!(tracks[1].track_type, TrackType::Audio);
+    }
+
+    #[test]
+    fn serialize_request_set_property_sid_no() {
+        let request = Request {
+            command: Command::SetProperty(Property::Sid.into(), TrackSelection::No.into()),
+            request_id: 1,
+        };
+        let json = serde_json::to_string(&request).unwrap();
+        assert_eq!(
+            json,
+            r#"{"command":["set_property","sid","no"],"request_id":1}"#
+        );
+    }
+
+    #[test]
+    fn deserialize_response_with_data_chapter_list() {
+        let input = r#"{ "data": [{ "title": "Intro", "time": 0.0 }, { "time": 93.5 }], "error": "success" }"#;
+        let response: CommandResponse = serde_json::from_str(input).unwrap();
+        let chapters: Vec<Chapter> = TryFromValue::try_from(response.data).unwrap();
+        assert_eq!(
+            chapters,
+            vec![
+                Chapter {
+                    title: Some(String::from("Intro")),
+                    time: 0.0,
+                },
+                Chapter {
+                    title: None,
+                    time: 93.5,
+                },
+            ]
+        )
+    }
+
+    #[test]
+    fn serialize_request_add_chapter() {
+        let request = Request {
+            command: Command::Add(Property::Chapter, Value::from(-1)),
+            request_id: 1,
+        };
+        let json = serde_json::to_string(&request).unwrap();
+        assert_eq!(json, r#"{"command":["add","chapter",-1],"request_id":1}"#);
+    }
+
+    #[test]
+    fn deserialize_event_with_custom_property_name() {
+        let input = r#"{ "event": "property-change", "id": 1, "data": "Artist", "name": "metadata/by-key/Artist" }"#;
+        let response: EventResponse = serde_json::from_str(input).unwrap();
+        assert_eq!(
+            response,
+            EventResponse {
+                event: Event::PropertyChange(PropertyChangeEvent {
+                    data: Value::from("Artist"),
+                    name: PropertyName::metadata_by_key("Artist")
+                }),
+                id: Some(1),
+                error: None,
+            }
+        )
+    }
+
+    #[test]
+    fn deserialize_event_with_unknown_property_name() {
+        let input = r#"{ "event": "property-change", "id": 1, "data": true, "name": "some-future-property" }"#;
+        let response: EventResponse = serde_json::from_str(input).unwrap();
+        assert_eq!(
+            response,
+            EventResponse {
+                event: Event::PropertyChange(PropertyChangeEvent {
+                    data: Value::from(true),
+                    name: PropertyName::Custom(String::from("some-future-property"))
+                }),
+                id: Some(1),
+                error: None,
+            }
+        )
+    }
+
+    #[test]
+    fn deserialize_event_with_sub_property_name_without_data() {
+        let input = r#"{ "event": "property-change", "id": 2, "name": "track-list/2/lang" }"#;
+        let response: EventResponse = serde_json::from_str(input).unwrap();
+        assert_eq!(
+            response,
+            EventResponse {
+                event: Event::PropertyChange(PropertyChangeEvent {
+                    data: Value::Null,
+                    name: Property::TrackList.sub_property("2/lang")
+                }),
+                id: Some(2),
+                error: None,
+            }
+        )
+    }
+
+    #[test]
+    fn deserialize_event_end_file_with_unknown_reason() {
+        let input =
+            r#"{ "event": "end-file", "reason": "some-future-reason", "playlist_entry_id": 1 }"#;
+        let response: EventResponse = serde_json::from_str(input).unwrap();
+        assert_eq!(
+            response,
+            EventResponse {
+                event: Event::EndFile(EndFileEvent {
+                    reason: Some(Reason::Unknown),
+                    playlist_entry_id: Some(1),
+                    file_error: None,
+                    playlist_insert_id: None,
+                    playlist_insert_num_entries: None,
+                }),
+                id: None,
+                error: None,
+            }
+        )
+    }
+
+    #[test]
+    fn deserialize_unknown_event() {
+        let input = r#"{ "event": "some-future-event", "data": 1 }"#;
+        let response: EventResponse = serde_json::from_str(input).unwrap();
+        assert_eq!(
+            response,
+            EventResponse {
+                event: Event::Other,
+                id: None,
+                error: None,
+            }
+        )
+    }
+
+    #[test]
+    fn deserialize_event_log_message() {
+        let input = r#"{ "event": "log-message", "prefix": "cplayer", "level": "v", "text": "Starting playback...\n" }"#;
+        let response: EventResponse = serde_json::from_str(input).unwrap();
+        let log_message = match response.event {
+            Event::LogMessage(log_message) => log_message,
+            event => panic!("expected log message, but got: {:?}", event),
+        };
+        assert_eq!(
+            log_message,
+            LogMessageEvent {
+                prefix: String::from("cplayer"),
+                level: String::from("v"),
+                text: String::from("Starting playback...\n"),
+            }
+        );
+        assert_eq!(log_message.log_level(), Some(MpvLogLevel::V));
+    }
+
+    #[test]
+    fn deserialize_event_hook() {
+        let input = r#"{ "event": "hook", "id": 1, "hook_id": 42 }"#;
+        let response: EventResponse = serde_json::from_str(input).unwrap();
+        assert_eq!(
+            response,
+            EventResponse {
+                event: Event::Hook(HookEvent { id: 1, hook_id: 42 }),
+                id: Some(1),
+                error: None,
+            }
+        )
+    }
+
+    #[test]
+    fn deserialize_event_client_message() {
+        let input = r#"{ "event": "client-message", "args": ["toggle-sidebar", "1"] }"#;
+        let response: EventResponse = serde_json::from_str(input).unwrap();
+        assert_eq!(
+            response,
+            EventResponse {
+                event: Event::ClientMessage(ClientMessageEvent {
+                    args: vec![String::from("toggle-sidebar"), String::from("1")],
+                }),
+                id: None,
+                error: None,
+            }
+        )
+    }
+
     #[test]
     fn deserialize_event() {
         let input = r#"{ "event": "property-change", "id": 1, "data": 52.0, "name": "volume" }"#;
@@ -169,7 +697,7 @@ mod tests {
             EventResponse {
                 event: Event::PropertyChange(PropertyChangeEvent {
                     data: Value::from(52.0),
-                    name: Property::Volume
+                    name: PropertyName::Known(Property::Volume)
                 }),
                 id: Some(1),
                 error: None,
@@ -0,0 +1,159 @@
+use serde::Deserialize;
+
+use crate::property::deserialize_value;
+use crate::{Result, TryFromValue, Value};
+
+/// Entry of the [`Property::TrackList`].
+///
+/// [`Property::TrackList`]: ./enum.Property.html#variant.TrackList
+#[derive(Debug, Clone, Deserialize, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub struct Track {
+    /// The ID as it's used for `--sid`/`--aid`/`--vid`.
+    ///
+    /// This is unique within tracks of the same type (sub/audio/video), but otherwise not.
+    pub id: i64,
+    /// The media type of the track.
+    #[serde(rename = "type")]
+    pub track_type: TrackType,
+    /// Track ID as used in the source file.
+    ///
+    /// Not always available.
+    /// (It is missing if the format has no native ID,
+    /// if the track is a pseudo-track that does not exist in this way in the actual file,
+    /// or if the format is handled by libavformat,
+    /// and the format was not whitelisted as having track IDs.)
+    pub src_id: Option<i64>,
+    /// Track title as it is stored in the file.
+    ///
+    /// Not always available.
+    pub title: Option<String>,
+    /// Track language as identified by the file.
+    ///
+    /// Not always available.
+    pub lang: Option<String>,
+    /// `true` if this is a video track that consists of a single picture.
+    ///
+    /// This is used for video tracks that are really attached pictures in audio files.
+    #[serde(default)]
+    pub albumart: bool,
+    /// `true` if the track has the default flag set in the file.
+    #[serde(default)]
+    pub default: bool,
+    /// `true` if the track has the forced flag set in the file.
+    #[serde(default)]
+    pub forced: bool,
+    /// The codec name used by this track, for example `h264`.
+    ///
+    /// Unavailable in some rare cases.
+    pub codec: Option<String>,
+    /// `true` if the track is an external file.
+    ///
+    /// This is set for separate subtitle files.
+    #[serde(default)]
+    pub external: bool,
+    /// The filename if the track is from an external file.
+    pub external_filename: Option<String>,
+    /// `true` if the track is currently decoded.
+    #[serde(default)]
+    pub selected: bool,
+    /// The stream index as usually used by the FFmpeg utilities.
+    ///
+    /// Note that this can be potentially wrong
+    /// if a demuxer other than libavformat (`--demuxer=lavf`) is used.
+    pub ff_index: Option<i64>,
+    /// If this track is being decoded, the human-readable decoder name.
+    pub decoder_desc: Option<String>,
+    /// Video width hint as indicated by the container. (Not always accurate.)
+    pub demux_w: Option<i64>,
+    /// Video height hint as indicated by the container. (Not always accurate.)
+    pub demux_h: Option<i64>,
+    /// Number of audio channels as indicated by the container.
+    ///
+    /// (Not always accurate - in particular,
+    /// the track could be decoded as a different number of channels.)
+    pub demux_channel_count: Option<i64>,
+    /// Channel layout as indicated by the container. (Not always accurate.)
+    pub demux_channels: Option<String>,
+    /// Audio sample rate as indicated by the container. (Not always accurate.)
+    pub demux_samplerate: Option<i64>,
+    /// Video FPS as indicated by the container. (Not always accurate.)
+    pub demux_fps: Option<f64>,
+    /// Audio average bitrate, in bits per second. (Not always accurate.)
+    pub demux_bitrate: Option<i64>,
+    /// Video clockwise rotation metadata, in degrees.
+    pub demux_rotation: Option<i64>,
+    /// Pixel aspect ratio.
+    pub demux_par: Option<f64>,
+    /// Deprecated alias for `demux_channel_count`.
+    pub audio_channels: Option<i64>,
+    /// Per-track replaygain peak.
+    ///
+    /// Only available for audio tracks with corresponding information stored in the source file.
+    pub replaygain_track_peak: Option<f64>,
+    /// Per-track replaygain gain.
+    ///
+    /// Only available for audio tracks with corresponding information stored in the source file.
+    pub replaygain_track_gain: Option<f64>,
+    /// Per-album replaygain peak.
+    ///
+    /// If the file has per-track but no per-album information,
+    /// the per-album values will be copied from the per-track values currently.
+    pub replaygain_album_peak: Option<f64>,
+    /// Per-album replaygain gain.
+    ///
+    /// If the file has per-track but no per-album information,
+    /// the per-album values will be copied from the per-track values currently.
+    pub replaygain_album_gain: Option<f64>,
+}
+
+impl TryFromValue for Track {
+    fn try_from(value: Value) -> Result<Track> {
+        deserialize_value(value)
+    }
+}
+
+/// Media type of a [`Track`].
+///
+/// [`Track`]: ./struct.Track.html
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TrackType {
+    /// Audio track.
+    Audio,
+    /// Video track.
+    Video,
+    /// Subtitle track.
+    Sub,
+    /// Track type unknown to this library, like one added by a newer mpv version.
+    #[serde(other)]
+    Unknown,
+}
+
+/// Track to select with [`MpvSocket::set_audio_track`],
+/// [`MpvSocket::set_sub_track`] or [`MpvSocket::set_video_track`].
+///
+/// [`MpvSocket::set_audio_track`]: ./struct.MpvSocket.html#method.set_audio_track
+/// [`MpvSocket::set_sub_track`]: ./struct.MpvSocket.html#method.set_sub_track
+/// [`MpvSocket::set_video_track`]: ./struct.MpvSocket.html#method.set_video_track
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum TrackSelection {
+    /// Select the track with the given [`Track::id`].
+    ///
+    /// [`Track::id`]: ./struct.Track.html#structfield.id
+    Id(i64),
+    /// Disable the track type.
+    No,
+    /// Select the default track.
+    Auto,
+}
+
+impl From<TrackSelection> for Value {
+    fn from(selection: TrackSelection) -> Self {
+        match selection {
+            TrackSelection::Id(id) => Value::from(id),
+            TrackSelection::No => Value::from("no"),
+            TrackSelection::Auto => Value::from("auto"),
+        }
+    }
+}
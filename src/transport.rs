@@ -0,0 +1,89 @@
+//! Platform-specific connections to mpv.
+//!
+//! Unix platforms connect to a Unix domain socket,
+//! Windows opens a named pipe.
+
+use std::io;
+use std::path::Path;
+
+use crate::{MpvSocketBuilder, ReadWrite};
+
+#[cfg(not(any(unix, windows)))]
+compile_error!("mpv-socket supports only Unix and Windows");
+
+#[cfg(unix)]
+pub(crate) fn open(path: &Path, builder: &MpvSocketBuilder) -> io::Result<Box<dyn ReadWrite>> {
+    let socket = connect_unix(
+        path,
+        builder.effective_read_timeout(),
+        builder.write_timeout,
+    )?;
+    Ok(Box::new(socket))
+}
+
+/// Connects to a Unix domain socket and sets its timeouts.
+#[cfg(unix)]
+pub(crate) fn connect_unix(
+    path: &Path,
+    read_timeout: Option<std::time::Duration>,
+    write_timeout: Option<std::time::Duration>,
+) -> io::Result<std::os::unix::net::UnixStream> {
+    use std::os::unix::net::UnixStream;
+
+    let socket = UnixStream::connect(path)?;
+    socket.set_read_timeout(read_timeout)?;
+    socket.set_write_timeout(write_timeout)?;
+
+    Ok(socket)
+}
+
+#[cfg(windows)]
+pub(crate) fn open(path: &Path, _builder: &MpvSocketBuilder) -> io::Result<Box<dyn ReadWrite>> {
+    use std::fs::OpenOptions;
+
+    use crate::error::ERROR_PIPE_BUSY;
+
+    let mut tries_left = 5u8;
+
+    loop {
+        let open_pipe_result = OpenOptions::new().read(true).write(true).open(path);
+
+        let error = match open_pipe_result {
+            Ok(socket) => {
+                return Ok(Box::new(socket));
+            }
+            Err(error) => match error.raw_os_error() {
+                Some(code) => match code {
+                    ERROR_PIPE_BUSY => {
+                        // On Windows the socket/pipe can only be opened
+                        // by one application and thread at the same time
+                        // and it can happen spuriously when closing/opening the connections
+                        // very often very fast, so try to guard against that.
+                        tries_left -= 1;
+                        if tries_left != 0 {
+                            std::thread::sleep(std::time::Duration::from_millis(10));
+                            continue;
+                        }
+
+                        error
+                    }
+                    _ => error,
+                },
+                None => error,
+            },
+        };
+
+        return Err(error);
+    }
+}
+
+/// Whether connecting again may succeed after the given error,
+/// e.g. because mpv has not created the socket yet.
+pub(crate) fn is_retryable(error: &io::Error) -> bool {
+    match error.kind() {
+        io::ErrorKind::NotFound | io::ErrorKind::ConnectionRefused => true,
+        #[cfg(windows)]
+        _ if error.raw_os_error() == Some(crate::error::ERROR_PIPE_BUSY) => true,
+        _ => false,
+    }
+}